@@ -9,6 +9,7 @@ use std::time::Duration;
 fn main() {
     let stdout = stdout();
     let mut stdout = stdout.lock().into_raw_mode().unwrap();
+    #[allow(clippy::unbuffered_bytes)]
     let mut stdin = async_stdin().bytes();
 
     write!(stdout,
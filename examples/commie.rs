@@ -4,7 +4,7 @@ use termion::{clear, color, cursor};
 
 use std::{time, thread};
 
-const COMMUNISM: &'static str = r#"
+const COMMUNISM: &str = r#"
               !#########       #                 
             !########!          ##!              
          !########!               ###            
@@ -0,0 +1,39 @@
+extern crate termion;
+
+use termion::event::{Event, Key};
+use termion::event_loop::{EventLoop, LoopEvent};
+use termion::raw::IntoRawMode;
+use std::io::{Write, stdout};
+use std::thread;
+use std::time::Duration;
+
+fn main() {
+    let mut stdout = stdout().into_raw_mode().unwrap();
+    let mut events = EventLoop::new().unwrap();
+    events.set_tick_rate(Some(Duration::from_secs(1) / 4));
+
+    let sender = events.sender();
+    thread::spawn(move || for i in 0.. {
+        thread::sleep(Duration::from_secs(3));
+        if sender.send(i).is_err() {
+            return;
+        }
+    });
+
+    write!(stdout, "{}{}q to exit. Type stuff or resize the window.\r\n",
+           termion::clear::All,
+           termion::cursor::Goto(1, 1))
+            .unwrap();
+    stdout.flush().unwrap();
+
+    let mut spinner = "|/-\\".chars().cycle();
+    for event in events {
+        match event.unwrap() {
+            LoopEvent::Input(Event::Key(Key::Char('q'))) => break,
+            LoopEvent::Input(event) => write!(stdout, "\r{:?}\r\n", event).unwrap(),
+            LoopEvent::User(i) => write!(stdout, "\rmessage #{} from the worker thread\r\n", i).unwrap(),
+            LoopEvent::Tick => write!(stdout, "\r{}", spinner.next().unwrap()).unwrap(),
+        }
+        stdout.flush().unwrap();
+    }
+}
@@ -1,7 +1,7 @@
 extern crate termion;
 
 use termion::event::*;
-use termion::cursor::{self, DetectCursorPos};
+use termion::cursor;
 use termion::input::{TermRead, MouseTerminal};
 use termion::raw::IntoRawMode;
 use std::io::{self, Write};
@@ -25,14 +25,12 @@ fn main() {
                     MouseEvent::Press(_, a, b) |
                     MouseEvent::Release(a, b) |
                     MouseEvent::Hold(a, b) => {
-                        write!(stdout, "{}", cursor::Goto(a, b)).unwrap();
-                        let (x, y) = stdout.cursor_pos().unwrap();
                         write!(stdout,
-                               "{}{}Cursor is at: ({},{}){}",
+                               "{}{}Mouse is at: ({},{}){}",
                                cursor::Goto(5, 5),
                                termion::clear::UntilNewline,
-                               x,
-                               y,
+                               a,
+                               b,
                                cursor::Goto(a, b))
                                 .unwrap();
                     }
@@ -1,30 +1,29 @@
 extern crate termion;
 
+use termion::color::Hsv;
 use termion::event::Key;
 use termion::input::TermRead;
 use termion::raw::IntoRawMode;
 use std::io::{Write, stdout, stdin};
 
-fn rainbow<W: Write>(stdout: &mut W, blue: u8) {
+fn rainbow<W: Write>(stdout: &mut W, saturation: f64) {
     write!(stdout,
            "{}{}",
            termion::cursor::Goto(1, 1),
            termion::clear::All)
             .unwrap();
 
-    for red in 0..32 {
-        let red = red * 8;
-        for green in 0..64 {
-            let green = green * 4;
-            write!(stdout,
-                   "{} ",
-                   termion::color::Bg(termion::color::Rgb(red, green, blue)))
-                    .unwrap();
+    for row in 0..32 {
+        let value = 1.0 - f64::from(row) / 32.0;
+        for column in 0..64 {
+            let hue = f64::from(column) * 360.0 / 64.0;
+            let color = Hsv { hue, saturation, value }.to_rgb();
+            write!(stdout, "{} ", termion::color::Bg(color)).unwrap();
         }
         write!(stdout, "\n\r").unwrap();
     }
 
-    writeln!(stdout, "{}b = {}", termion::style::Reset, blue).unwrap();
+    writeln!(stdout, "{}s = {:.2}", termion::style::Reset, saturation).unwrap();
 }
 
 fn main() {
@@ -32,23 +31,23 @@ fn main() {
     let mut stdout = stdout().into_raw_mode().unwrap();
 
     writeln!(stdout,
-             "{}{}{}Use the up/down arrow keys to change the blue in the rainbow.",
+             "{}{}{}Use the up/down arrow keys to change the saturation of the rainbow.",
              termion::clear::All,
              termion::cursor::Goto(1, 1),
              termion::cursor::Hide)
             .unwrap();
 
-    let mut blue = 172u8;
+    let mut saturation = 0.7f64;
 
     for c in stdin.keys() {
         match c.unwrap() {
             Key::Up => {
-                blue = blue.saturating_add(4);
-                rainbow(&mut stdout, blue);
+                saturation = (saturation + 0.05).min(1.0);
+                rainbow(&mut stdout, saturation);
             }
             Key::Down => {
-                blue = blue.saturating_sub(4);
-                rainbow(&mut stdout, blue);
+                saturation = (saturation - 0.05).max(0.0);
+                rainbow(&mut stdout, saturation);
             }
             Key::Char('q') => break,
             _ => {}
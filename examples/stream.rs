@@ -0,0 +1,35 @@
+extern crate futures_core;
+extern crate termion;
+extern crate tokio;
+
+use futures_core::Stream;
+use std::future;
+use std::io::{Write, stdout};
+use std::pin::Pin;
+use termion::event::{Event, Key};
+use termion::raw::IntoRawMode;
+use termion::stream::EventStream;
+
+fn main() {
+    let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+    let _guard = rt.enter();
+
+    let mut stdout = stdout().into_raw_mode().unwrap();
+    let mut events = EventStream::new().unwrap();
+
+    write!(stdout, "{}{}q to exit. Type stuff or resize the window.\r\n",
+           termion::clear::All,
+           termion::cursor::Goto(1, 1))
+            .unwrap();
+    stdout.flush().unwrap();
+
+    loop {
+        let next = future::poll_fn(|cx| Pin::new(&mut events).poll_next(cx));
+        match rt.block_on(next) {
+            Some(Ok(Event::Key(Key::Char('q')))) | None => break,
+            Some(Ok(event)) => write!(stdout, "{:?}\r\n", event).unwrap(),
+            Some(Err(e)) => write!(stdout, "error: {}\r\n", e).unwrap(),
+        }
+        stdout.flush().unwrap();
+    }
+}
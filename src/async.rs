@@ -1,7 +1,9 @@
+use std::collections::VecDeque;
 use std::io::{self, Read};
 use std::sync::mpsc;
 use std::thread;
 
+use query::Unread;
 use sys::tty::get_tty;
 
 /// Construct an asynchronous handle to the TTY standard input.
@@ -14,6 +16,7 @@ use sys::tty::get_tty;
 /// asyncronized from piped input would rarely make sense. In other words, if you pipe standard
 /// output from another process, it won't be reflected in the stream returned by this function, as
 /// this represents the TTY device, and not the piped standard input.
+#[allow(clippy::unbuffered_bytes)]
 pub fn async_stdin() -> AsyncReader {
     let (send, recv) = mpsc::channel();
 
@@ -23,7 +26,10 @@ pub fn async_stdin() -> AsyncReader {
                       }
                   });
 
-    AsyncReader { recv: recv }
+    AsyncReader {
+        recv,
+        unread: VecDeque::new(),
+    }
 }
 
 /// An asynchronous reader.
@@ -33,6 +39,8 @@ pub fn async_stdin() -> AsyncReader {
 pub struct AsyncReader {
     /// The underlying mpsc receiver.
     recv: mpsc::Receiver<io::Result<u8>>,
+    /// The bytes put back with `Unread`, to be read first.
+    unread: VecDeque<u8>,
 }
 
 // FIXME: Allow constructing an async reader from an arbitrary stream.
@@ -44,7 +52,7 @@ impl Read for AsyncReader {
     /// bytes written is lower than the buffer's length, the event queue is empty or that the event
     /// stream halted.
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        let mut total = 0;
+        let mut total = self.unread.read(buf)?;
 
         loop {
             if total >= buf.len() {
@@ -65,12 +73,21 @@ impl Read for AsyncReader {
     }
 }
 
+impl Unread for AsyncReader {
+    fn unread(&mut self, bytes: &[u8]) {
+        for &byte in bytes.iter().rev() {
+            self.unread.push_front(byte);
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use std::io::Read;
 
     #[test]
+    #[allow(clippy::unbuffered_bytes)]
     fn test_async_stdin() {
         let stdin = async_stdin();
         stdin.bytes().next();
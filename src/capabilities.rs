@@ -0,0 +1,382 @@
+//! Detecting what the terminal can do.
+//!
+//! `Capabilities::from_env` guesses from the environment variables (`TERM`, `COLORTERM`,
+//! `TERM_PROGRAM`, `NO_COLOR`, and those set by tmux and screen), while `Capabilities::detect`
+//! also asks the terminal, with `identity::query_terminal_identity` and `mode::query_modes`.
+//! The colors are only guessed: see `ColorDepth::query` to ask the terminal about them.
+//!
+//! Inside tmux or screen, the answers come from the multiplexer rather than from the terminal
+//! around it, which is what matters since the multiplexer translates the output.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use termion::async_stdin;
+//! use termion::capabilities::Capabilities;
+//! use termion::color::ColorDepth;
+//! use termion::query::DEFAULT_TIMEOUT;
+//! use termion::raw::IntoRawMode;
+//! use std::io::stdout;
+//!
+//! fn main() {
+//!     let mut stdout = stdout().into_raw_mode().unwrap();
+//!     let mut stdin = async_stdin();
+//!
+//!     let caps = Capabilities::detect(&mut stdout, &mut stdin, DEFAULT_TIMEOUT).unwrap();
+//!     if caps.colors == ColorDepth::TrueColor {
+//!         println!("All the colors.\r");
+//!     }
+//!     if caps.synchronized_output {
+//!         println!("No tearing.\r");
+//!     }
+//! }
+//! ```
+
+use std::env;
+use std::io::{self, Read, Write};
+use std::time::Duration;
+
+use color::{self, ColorDepth};
+use error::Error;
+use identity::{query_terminal_identity, Feature, TerminalIdentity};
+use mode::{self, query_modes, ModeState};
+use query::Unread;
+
+/// A terminal multiplexer running between the program and the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Multiplexer {
+    /// tmux.
+    Tmux,
+    /// GNU screen.
+    Screen,
+}
+
+/// How the terminal measures the width of text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnicodeWidth {
+    /// Each code point takes its own width, as given by `wcwidth`.
+    CodePoints,
+    /// Each code point takes its own width, but grapheme clustering can be turned on by setting
+    /// `mode::GRAPHEME_CLUSTERING`.
+    Switchable,
+    /// Each grapheme cluster, such as an emoji sequence, takes the width of a single character.
+    Graphemes,
+}
+
+/// The mouse report encodings understood by the terminal. See `input::MouseTerminal`.
+///
+/// More encodings may be added, so the struct cannot be built outside of termion.
+#[allow(clippy::manual_non_exhaustive)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct MouseEncodings {
+    /// The X11 encoding, limited to 223 columns and rows.
+    pub x11: bool,
+    /// The SGR encoding.
+    pub sgr: bool,
+    /// The urxvt encoding.
+    pub urxvt: bool,
+    _is_not_complete: (),
+}
+
+/// The image protocols understood by the terminal.
+///
+/// More protocols may be added, so the struct cannot be built outside of termion.
+#[allow(clippy::manual_non_exhaustive)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ImageProtocols {
+    /// Sixel graphics.
+    pub sixel: bool,
+    /// The kitty graphics protocol.
+    pub kitty: bool,
+    /// The inline images of iTerm2.
+    pub iterm2: bool,
+    _is_not_complete: (),
+}
+
+/// What the terminal can do.
+///
+/// Most of these are educated guesses: a missing capability may still work, and the user may
+/// have set the environment wrongly.
+///
+/// More capabilities may be added, so the struct cannot be built outside of termion: use
+/// `from_env` or `detect`.
+#[allow(clippy::manual_non_exhaustive)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    /// The colors which can be shown.
+    pub colors: ColorDepth,
+    /// The multiplexer in use, if any.
+    pub multiplexer: Option<Multiplexer>,
+    /// How the width of text is measured.
+    pub unicode_width: UnicodeWidth,
+    /// The mouse report encodings.
+    pub mouse: MouseEncodings,
+    /// Whether pasted text can be told apart from typed text.
+    pub bracketed_paste: bool,
+    /// Whether the display can be held until a frame is complete.
+    pub synchronized_output: bool,
+    /// Whether focus changes can be reported.
+    pub focus_events: bool,
+    /// Whether OSC 8 hyperlinks are shown.
+    pub hyperlinks: bool,
+    /// The image protocols.
+    pub images: ImageProtocols,
+    /// What the terminal said about itself, if it was asked and answered.
+    pub identity: Option<TerminalIdentity>,
+    _is_not_complete: (),
+}
+
+/// The environment variables of interest.
+#[derive(Debug, Default)]
+struct Env {
+    term: String,
+    colorterm: String,
+    term_program: String,
+    no_color: bool,
+    tmux: bool,
+    screen: bool,
+    vte_version: u32,
+}
+
+impl Env {
+    fn current() -> Env {
+        let var = |name: &str| env::var(name).unwrap_or_default();
+
+        Env {
+            term: var("TERM"),
+            colorterm: var("COLORTERM"),
+            term_program: var("TERM_PROGRAM"),
+            // See https://no-color.org: it only counts when it is not empty.
+            no_color: !var("NO_COLOR").is_empty(),
+            tmux: env::var_os("TMUX").is_some(),
+            screen: env::var_os("STY").is_some(),
+            vte_version: var("VTE_VERSION").parse().unwrap_or(0),
+        }
+    }
+}
+
+/// The beginnings of `TERM` for terminals which follow xterm for mouse, paste and focus.
+const XTERM_LIKE: &[&str] = &["xterm", "rxvt", "screen", "tmux", "alacritty", "foot", "wezterm",
+                              "contour", "konsole", "st-", "vte", "gnome"];
+
+impl Capabilities {
+    /// Guess the capabilities from the environment variables, without asking the terminal.
+    pub fn from_env() -> Capabilities {
+        Capabilities::from_vars(&Env::current())
+    }
+
+    /// Guess the capabilities from the environment variables, and refine them with the answers of
+    /// the terminal, read from `reader`.
+    ///
+    /// Every query waits at most `timeout`, and a terminal which does not answer keeps the
+    /// guesses. The terminal must be in raw mode. The other input read meanwhile is put back into
+    /// `reader`, see the `query` module.
+    pub fn detect<W, R>(writer: &mut W, reader: &mut R, timeout: Duration)
+                        -> io::Result<Capabilities>
+        where W: Write + ?Sized,
+              R: Read + Unread + ?Sized
+    {
+        let mut caps = Capabilities::from_env();
+        caps.query(writer, reader, timeout)?;
+        Ok(caps)
+    }
+
+    fn from_vars(env: &Env) -> Capabilities {
+        let term = &env.term[..];
+        let program = &env.term_program[..];
+
+        let multiplexer = if env.tmux || term.starts_with("tmux") {
+            Some(Multiplexer::Tmux)
+        } else if env.screen || term.starts_with("screen") {
+            Some(Multiplexer::Screen)
+        } else {
+            None
+        };
+
+        let mut colors = color::from_vars(term, &env.colorterm, env.no_color);
+        if multiplexer == Some(Multiplexer::Screen) {
+            // screen does not pass truecolor through, whatever `COLORTERM` says.
+            colors = colors.min(ColorDepth::Ansi256);
+        }
+
+        let xterm_like = XTERM_LIKE.iter().any(|prefix| term.starts_with(prefix));
+        let kitty = term == "xterm-kitty";
+        let ghostty = term == "xterm-ghostty" || program == "ghostty";
+        let wezterm = term.starts_with("wezterm") || program == "WezTerm";
+        let iterm2 = program == "iTerm.app";
+
+        Capabilities {
+            colors,
+            multiplexer,
+            unicode_width: UnicodeWidth::CodePoints,
+            mouse: MouseEncodings {
+                x11: xterm_like,
+                sgr: xterm_like,
+                urxvt: term.starts_with("rxvt"),
+                _is_not_complete: (),
+            },
+            bracketed_paste: xterm_like,
+            synchronized_output: false,
+            focus_events: xterm_like,
+            hyperlinks: kitty || ghostty || wezterm || iterm2 || program == "vscode" ||
+                        term.starts_with("foot") || term.starts_with("contour") ||
+                        env.vte_version >= 5000,
+            images: ImageProtocols {
+                sixel: term.starts_with("foot") || term.starts_with("contour"),
+                kitty: kitty || ghostty || wezterm,
+                iterm2: iterm2 || wezterm,
+                _is_not_complete: (),
+            },
+            identity: None,
+            _is_not_complete: (),
+        }
+    }
+
+    /// Refine the capabilities with the answers of the terminal.
+    fn query<W, R>(&mut self, writer: &mut W, reader: &mut R, timeout: Duration) -> io::Result<()>
+        where W: Write + ?Sized,
+              R: Read + Unread + ?Sized
+    {
+        let identity = match query_terminal_identity(writer, reader, timeout)? {
+            Some(identity) => identity,
+            None => return Ok(()),
+        };
+
+        let modes = [mode::MOUSE_X11,
+                     mode::MOUSE_SGR,
+                     mode::MOUSE_URXVT,
+                     mode::BRACKETED_PASTE,
+                     mode::SYNCHRONIZED_OUTPUT,
+                     mode::FOCUS_EVENT,
+                     mode::GRAPHEME_CLUSTERING];
+        // The terminal answered its identity, so a timeout only means the modes are unknown.
+        let states = match query_modes(writer, reader, &modes, timeout).map_err(Error::from) {
+            Ok(states) => states,
+            Err(Error::QueryTimeout { .. }) => vec![ModeState::NotRecognized; modes.len()],
+            Err(e) => return Err(e.into()),
+        };
+
+        // Terminals without DECRQM report nothing, so the answers only add to the guesses.
+        self.mouse.x11 |= states[0].is_supported();
+        self.mouse.sgr |= states[1].is_supported();
+        self.mouse.urxvt |= states[2].is_supported();
+        self.bracketed_paste |= states[3].is_supported();
+        self.synchronized_output |= states[4].is_supported();
+        self.focus_events |= states[5].is_supported();
+        self.unicode_width = match states[6] {
+            ModeState::Set | ModeState::PermanentlySet => UnicodeWidth::Graphemes,
+            ModeState::Reset => UnicodeWidth::Switchable,
+            ModeState::NotRecognized | ModeState::PermanentlyReset => self.unicode_width,
+        };
+
+        self.images.sixel |= identity.supports(Feature::Sixel);
+        match identity.name.as_ref().map(|name| name.to_lowercase()) {
+            Some(ref name) if name == "kitty" || name == "ghostty" => {
+                self.hyperlinks = true;
+                self.images.kitty = true;
+            }
+            Some(ref name) if name == "wezterm" => {
+                self.hyperlinks = true;
+                self.images.kitty = true;
+                self.images.iterm2 = true;
+            }
+            Some(ref name) if name == "iterm2" => {
+                self.hyperlinks = true;
+                self.images.iterm2 = true;
+            }
+            Some(ref name) if name == "foot" || name == "contour" => self.hyperlinks = true,
+            _ => {}
+        }
+
+        self.identity = Some(identity);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use query::Pushback;
+
+    #[test]
+    fn test_from_vars() {
+        let caps = Capabilities::from_vars(&Env {
+                                               term: "xterm-256color".to_owned(),
+                                               colorterm: "truecolor".to_owned(),
+                                               ..Env::default()
+                                           });
+        assert_eq!(caps.colors, ColorDepth::TrueColor);
+        assert_eq!(caps.multiplexer, None);
+        assert!(caps.mouse.sgr);
+        assert!(!caps.mouse.urxvt);
+        assert!(caps.bracketed_paste);
+        assert!(!caps.hyperlinks);
+
+        let caps = Capabilities::from_vars(&Env {
+                                               term: "screen-256color".to_owned(),
+                                               colorterm: "truecolor".to_owned(),
+                                               screen: true,
+                                               ..Env::default()
+                                           });
+        assert_eq!(caps.colors, ColorDepth::Ansi256);
+        assert_eq!(caps.multiplexer, Some(Multiplexer::Screen));
+
+        let caps = Capabilities::from_vars(&Env {
+                                               term: "xterm-kitty".to_owned(),
+                                               no_color: true,
+                                               ..Env::default()
+                                           });
+        assert_eq!(caps.colors, ColorDepth::NoColor);
+        assert!(caps.hyperlinks);
+        assert!(caps.images.kitty);
+
+        let caps = Capabilities::from_vars(&Env {
+                                               term: "dumb".to_owned(),
+                                               ..Env::default()
+                                           });
+        assert_eq!(caps.colors, ColorDepth::NoColor);
+        assert!(!caps.mouse.x11);
+    }
+
+    #[test]
+    fn test_query() {
+        let mut caps = Capabilities::from_vars(&Env {
+                                                   term: "vt220".to_owned(),
+                                                   ..Env::default()
+                                               });
+        let input = b"\x1BP>|foot(1.16.2)\x1B\\\x1B[>1;11602;0c\x1B[?62;4;22c\
+                      \x1B[?1006;2$y\x1B[?2026;2$y\x1B[?2027;0$y\x1B[?62;4;22c";
+        let mut reader = Pushback::new(&input[..]);
+        caps.query(&mut Vec::new(), &mut reader, Duration::from_millis(10)).unwrap();
+
+        assert!(caps.mouse.sgr);
+        assert!(!caps.mouse.x11);
+        assert!(caps.synchronized_output);
+        assert!(!caps.bracketed_paste);
+        assert_eq!(caps.unicode_width, UnicodeWidth::CodePoints);
+        assert!(caps.hyperlinks);
+        assert!(caps.images.sixel);
+        assert_eq!(caps.identity.unwrap().name, Some("foot".to_owned()));
+
+        // A terminal answering its identity, but not the modes, keeps the guesses for them.
+        let mut caps = Capabilities::from_vars(&Env {
+                                                   term: "xterm".to_owned(),
+                                                   ..Env::default()
+                                               });
+        let input = b"\x1B[>41;330;0c\x1B[?64;4c";
+        let mut reader = Pushback::new(&input[..]);
+        caps.query(&mut Vec::new(), &mut reader, Duration::from_millis(10)).unwrap();
+        assert!(caps.mouse.sgr);
+        assert!(!caps.synchronized_output);
+        assert_eq!(caps.unicode_width, UnicodeWidth::CodePoints);
+        assert!(caps.images.sixel);
+        assert!(caps.identity.is_some());
+
+        // A terminal which does not answer keeps the guesses.
+        let mut caps = Capabilities::from_vars(&Env::default());
+        let expected = caps.clone();
+        let mut reader = Pushback::new(&b""[..]);
+        caps.query(&mut Vec::new(), &mut reader, Duration::from_millis(10)).unwrap();
+        assert_eq!(caps, expected);
+    }
+}
@@ -11,13 +11,35 @@
 //!     println!("{}Back again", color::Fg(color::Reset));
 //! }
 //! ```
+//!
+//! The 16 named colors, from `Black` to `LightWhite`, are written with the classic SGR codes
+//! (`30`–`37` and `90`–`97` for the foreground, `40`–`47` and `100`–`107` for the background),
+//! which every color terminal understands. To write them as palette indexes (`38;5;N`) instead,
+//! convert them to `AnsiValue`:
+//!
+//! ```rust
+//! use termion::color;
+//!
+//! assert_eq!(color::Fg(color::Red).to_string(), "\x1B[31m");
+//! assert_eq!(color::Fg(color::AnsiValue::from(color::Red)).to_string(), "\x1B[38;5;1m");
+//! ```
 
 use std::fmt;
-use raw::CONTROL_SEQUENCE_TIMEOUT;
 use std::io::{self, Write, Read};
-use std::time::{SystemTime, Duration};
+use std::ops;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::str::{self, FromStr};
+use std::time::Duration;
 use async::async_stdin;
 use std::env;
+use error::Error;
+use query::{find_sequence, find_string, query, query_many, Unread, DEFAULT_TIMEOUT};
+use sys::tty::is_tty;
+
+mod space;
+mod x11;
+
+pub use self::space::{gradient, Hsl, Hsv, LinearRgb};
 
 /// A terminal color.
 pub trait Color {
@@ -28,43 +50,49 @@ pub trait Color {
 }
 
 macro_rules! derive_color {
-    ($doc:expr, $name:ident, $value:expr) => {
+    ($doc:expr, $name:ident, $value:expr, $fg:expr, $bg:expr) => {
         #[doc = $doc]
-        #[derive(Copy, Clone, Debug)]
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
         pub struct $name;
 
         impl Color for $name {
             #[inline]
             fn write_fg(&self, f: &mut fmt::Formatter) -> fmt::Result {
-                write!(f, csi!("38;5;", $value, "m"))
+                write!(f, csi!($fg, "m"))
             }
 
             #[inline]
             fn write_bg(&self, f: &mut fmt::Formatter) -> fmt::Result {
-                write!(f, csi!("48;5;", $value, "m"))
+                write!(f, csi!($bg, "m"))
+            }
+        }
+
+        impl From<$name> for AnsiValue {
+            fn from(_: $name) -> AnsiValue {
+                AnsiValue($value)
             }
         }
     };
 }
 
-derive_color!("Black.", Black, "0");
-derive_color!("Red.", Red, "1");
-derive_color!("Green.", Green, "2");
-derive_color!("Yellow.", Yellow, "3");
-derive_color!("Blue.", Blue, "4");
-derive_color!("Magenta.", Magenta, "5");
-derive_color!("Cyan.", Cyan, "6");
-derive_color!("White.", White, "7");
-derive_color!("High-intensity light black.", LightBlack, "8");
-derive_color!("High-intensity light red.", LightRed, "9");
-derive_color!("High-intensity light green.", LightGreen, "10");
-derive_color!("High-intensity light yellow.", LightYellow, "11");
-derive_color!("High-intensity light blue.", LightBlue, "12");
-derive_color!("High-intensity light magenta.", LightMagenta, "13");
-derive_color!("High-intensity light cyan.", LightCyan, "14");
-derive_color!("High-intensity light white.", LightWhite, "15");
-
-impl<'a> Color for &'a Color {
+derive_color!("Black.", Black, 0, "30", "40");
+derive_color!("Red.", Red, 1, "31", "41");
+derive_color!("Green.", Green, 2, "32", "42");
+derive_color!("Yellow.", Yellow, 3, "33", "43");
+derive_color!("Blue.", Blue, 4, "34", "44");
+derive_color!("Magenta.", Magenta, 5, "35", "45");
+derive_color!("Cyan.", Cyan, 6, "36", "46");
+derive_color!("White.", White, 7, "37", "47");
+derive_color!("High-intensity light black.", LightBlack, 8, "90", "100");
+derive_color!("High-intensity light red.", LightRed, 9, "91", "101");
+derive_color!("High-intensity light green.", LightGreen, 10, "92", "102");
+derive_color!("High-intensity light yellow.", LightYellow, 11, "93", "103");
+derive_color!("High-intensity light blue.", LightBlue, 12, "94", "104");
+derive_color!("High-intensity light magenta.", LightMagenta, 13, "95", "105");
+derive_color!("High-intensity light cyan.", LightCyan, 14, "96", "106");
+derive_color!("High-intensity light white.", LightWhite, 15, "97", "107");
+
+impl Color for &dyn Color {
     #[inline]
     fn write_fg(&self, f: &mut fmt::Formatter) -> fmt::Result {
         (*self).write_fg(f)
@@ -77,7 +105,7 @@ impl<'a> Color for &'a Color {
 }
 
 /// An arbitrary ANSI color value.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct AnsiValue(pub u8);
 
 impl AnsiValue {
@@ -108,8 +136,45 @@ impl AnsiValue {
 
         AnsiValue(0xE8 + shade)
     }
+
+    /// The RGB value of this color.
+    ///
+    /// The first 16 colors are set by the terminal: the defaults of xterm are assumed.
+    pub fn to_rgb(self) -> Rgb {
+        match self.0 {
+            n @ 0..=15 => BASE_COLORS[n as usize],
+            n @ 16..=231 => {
+                let level = |i: u8| CUBE_LEVELS[i as usize];
+                let n = n - 16;
+                Rgb(level(n / 36), level(n / 6 % 6), level(n % 6))
+            }
+            n => {
+                let gray = 8 + 10 * (n - 0xE8);
+                Rgb(gray, gray, gray)
+            }
+        }
+    }
+
+    /// The closest of the 16 base colors.
+    pub fn to_ansi16(self) -> AnsiValue {
+        if self.0 < 16 {
+            self
+        } else {
+            self.to_rgb().to_ansi16()
+        }
+    }
 }
 
+/// The defaults of xterm for the 16 base colors.
+const BASE_COLORS: [Rgb; 16] = [Rgb(0, 0, 0), Rgb(205, 0, 0), Rgb(0, 205, 0), Rgb(205, 205, 0),
+                                Rgb(0, 0, 238), Rgb(205, 0, 205), Rgb(0, 205, 205),
+                                Rgb(229, 229, 229), Rgb(127, 127, 127), Rgb(255, 0, 0),
+                                Rgb(0, 255, 0), Rgb(255, 255, 0), Rgb(92, 92, 255),
+                                Rgb(255, 0, 255), Rgb(0, 255, 255), Rgb(255, 255, 255)];
+
+/// The levels of each component in the 6×6×6 color cube.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
 impl Color for AnsiValue {
     #[inline]
     fn write_fg(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -123,7 +188,7 @@ impl Color for AnsiValue {
 }
 
 /// A truecolor RGB.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Rgb(pub u8, pub u8, pub u8);
 
 impl Color for Rgb {
@@ -138,8 +203,67 @@ impl Color for Rgb {
     }
 }
 
+impl Rgb {
+    /// The closest color of the 6×6×6 cube or of the grayscale ramp of the 256-color palette.
+    ///
+    /// The first 16 colors are left out, since they vary between terminals.
+    pub fn to_ansi256(self) -> AnsiValue {
+        // The closest level of each component in the cube.
+        let level = |v: u8| match v {
+            0..=47 => 0,
+            48..=114 => 1,
+            v => (v - 35) / 40,
+        };
+        let cube = AnsiValue(16 + 36 * level(self.0) + 6 * level(self.1) + level(self.2));
+
+        // The closest shade of gray, from 8 to 238.
+        let mean = (u16::from(self.0) + u16::from(self.1) + u16::from(self.2)) / 3;
+        let gray = AnsiValue::grayscale((mean.saturating_sub(3) / 10).min(23) as u8);
+
+        if distance(self, gray.to_rgb()) < distance(self, cube.to_rgb()) {
+            gray
+        } else {
+            cube
+        }
+    }
+
+    /// The closest of the 16 base colors, as `AnsiValue(0)` to `AnsiValue(15)`.
+    pub fn to_ansi16(self) -> AnsiValue {
+        let closest = (0..16).min_by(|&a, &b| {
+            distance(self, BASE_COLORS[a]).total_cmp(&distance(self, BASE_COLORS[b]))
+        });
+        AnsiValue(closest.unwrap_or(0) as u8)
+    }
+}
+
+/// The perceptual distance between two colors: the square of their distance in the CIELAB
+/// color space (the ΔE of CIE76).
+fn distance(a: Rgb, b: Rgb) -> f64 {
+    let (l1, a1, b1) = to_lab(a);
+    let (l2, a2, b2) = to_lab(b);
+    (l1 - l2).powi(2) + (a1 - a2).powi(2) + (b1 - b2).powi(2)
+}
+
+/// Convert an sRGB color to CIELAB, with the D65 white point.
+fn to_lab(color: Rgb) -> (f64, f64, f64) {
+    let LinearRgb(r, g, b) = color.to_linear();
+
+    let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+    let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+
+    let f = |t: f64| if t > 216.0 / 24389.0 {
+        t.cbrt()
+    } else {
+        (24389.0 / 27.0 * t + 16.0) / 116.0
+    };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+
+    (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+}
+
 /// Reset colors to defaults.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Reset;
 
 impl Color for Reset {
@@ -154,6 +278,239 @@ impl Color for Reset {
     }
 }
 
+/// Any color, chosen at runtime.
+///
+/// Unlike `&dyn Color`, it can be compared, hashed, and parsed from a string, such as those of a
+/// configuration file:
+///
+/// - `#rrggbb` or `#rgb`, in hexadecimal,
+/// - `rgb:rr/gg/bb`, with one to four hexadecimal digits for each component, as in X11,
+/// - `ansi(n)`, an `AnsiValue`,
+/// - `reset`, or one of the 16 named colors, such as `red` or `light blue`,
+/// - an X11 color name, such as `SteelBlue` or `dark olive green`.
+///
+/// Names and the `rgb:` and `ansi(` prefixes are case-insensitive, and spaces, `-` and `_` in
+/// names are ignored. The named colors win over the X11 colors of the same name: `red` is
+/// `AnyColor::Red`, whose value is set by the terminal.
+///
+/// # Example
+///
+/// ```rust
+/// use termion::color::{self, AnyColor};
+///
+/// let color: AnyColor = "#ff8000".parse().unwrap();
+/// assert_eq!(color, AnyColor::Rgb(color::Rgb(255, 128, 0)));
+/// assert_eq!("light_red".parse::<AnyColor>().unwrap(), AnyColor::LightRed);
+/// println!("{}Orange", color::Fg(color));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnyColor {
+    /// Black.
+    Black,
+    /// Red.
+    Red,
+    /// Green.
+    Green,
+    /// Yellow.
+    Yellow,
+    /// Blue.
+    Blue,
+    /// Magenta.
+    Magenta,
+    /// Cyan.
+    Cyan,
+    /// White.
+    White,
+    /// High-intensity light black.
+    LightBlack,
+    /// High-intensity light red.
+    LightRed,
+    /// High-intensity light green.
+    LightGreen,
+    /// High-intensity light yellow.
+    LightYellow,
+    /// High-intensity light blue.
+    LightBlue,
+    /// High-intensity light magenta.
+    LightMagenta,
+    /// High-intensity light cyan.
+    LightCyan,
+    /// High-intensity light white.
+    LightWhite,
+    /// An `AnsiValue` color.
+    Ansi(AnsiValue),
+    /// An `Rgb` color.
+    Rgb(Rgb),
+    /// The default color.
+    Reset,
+}
+
+macro_rules! any_color {
+    ($($name:ident),*) => {
+        impl AnyColor {
+            /// The color as a trait object.
+            fn as_color(&self) -> &dyn Color {
+                match *self {
+                    $(AnyColor::$name => &$name,)*
+                    AnyColor::Ansi(ref color) => color,
+                    AnyColor::Rgb(ref color) => color,
+                    AnyColor::Reset => &Reset,
+                }
+            }
+
+            /// The SGR parameters of the color, where `base` is 30 for the foreground, 40 for
+            /// the background, and 50 for the underline.
+            ///
+            /// The underline has no parameters of its own for the 16 named colors, so they are
+            /// written as their 256-color index, `58:5:n`. Its other colors are written in the
+            /// colon form too, the only one known for it: `58:5:n`, `58:2::r:g:b`, and `59` for
+            /// the default color.
+            pub(crate) fn sgr_params(self, base: u8) -> String {
+                match self {
+                    $(
+                        AnyColor::$name if base == 50 => {
+                            format!("58:5:{}", AnsiValue::from($name).0)
+                        }
+                        AnyColor::$name => ansi_param(base, AnsiValue::from($name), false),
+                    )*
+                    AnyColor::Ansi(color) if base == 50 => format!("58:5:{}", color.0),
+                    AnyColor::Ansi(color) => ansi_param(base, color, true),
+                    AnyColor::Rgb(Rgb(r, g, b)) if base == 50 => {
+                        format!("58:2::{}:{}:{}", r, g, b)
+                    }
+                    AnyColor::Rgb(Rgb(r, g, b)) => format!("{};2;{};{};{}", base + 8, r, g, b),
+                    AnyColor::Reset => (base + 9).to_string(),
+                }
+            }
+        }
+
+        $(
+            impl From<$name> for AnyColor {
+                fn from(_: $name) -> AnyColor {
+                    AnyColor::$name
+                }
+            }
+        )*
+    };
+}
+
+any_color!(Black, Red, Green, Yellow, Blue, Magenta, Cyan, White, LightBlack, LightRed,
+           LightGreen, LightYellow, LightBlue, LightMagenta, LightCyan, LightWhite);
+
+impl From<AnsiValue> for AnyColor {
+    fn from(color: AnsiValue) -> AnyColor {
+        AnyColor::Ansi(color)
+    }
+}
+
+impl From<Rgb> for AnyColor {
+    fn from(color: Rgb) -> AnyColor {
+        AnyColor::Rgb(color)
+    }
+}
+
+impl From<Reset> for AnyColor {
+    fn from(_: Reset) -> AnyColor {
+        AnyColor::Reset
+    }
+}
+
+impl Color for AnyColor {
+    #[inline]
+    fn write_fg(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_color().write_fg(f)
+    }
+
+    #[inline]
+    fn write_bg(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_color().write_bg(f)
+    }
+}
+
+impl FromStr for AnyColor {
+    type Err = Error;
+
+    /// Parse a color, failing with `Error::InvalidColor`.
+    fn from_str(s: &str) -> Result<AnyColor, Error> {
+        parse_color(s.trim()).ok_or_else(|| Error::InvalidColor { text: s.to_owned() })
+    }
+}
+
+/// Remove an ASCII prefix from `s`, whatever its case.
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    match s.get(..prefix.len()) {
+        Some(start) if start.eq_ignore_ascii_case(prefix) => Some(&s[prefix.len()..]),
+        _ => None,
+    }
+}
+
+/// Parse a color, as described by `AnyColor`.
+fn parse_color(s: &str) -> Option<AnyColor> {
+    if let Some(hex) = s.strip_prefix('#') {
+        let digits = decode_hex_digits(hex)?;
+        let rgb = match digits[..] {
+            [r, g, b] => Rgb(r * 17, g * 17, b * 17),
+            [r1, r2, g1, g2, b1, b2] => Rgb(r1 << 4 | r2, g1 << 4 | g2, b1 << 4 | b2),
+            _ => return None,
+        };
+        return Some(AnyColor::Rgb(rgb));
+    }
+    if let Some(spec) = strip_prefix_ignore_case(s, "rgb:") {
+        let components = spec.split('/').map(scale_hex).collect::<Option<Vec<u8>>>()?;
+        return match components[..] {
+            [r, g, b] => Some(AnyColor::Rgb(Rgb(r, g, b))),
+            _ => None,
+        };
+    }
+    if let Some(n) = strip_prefix_ignore_case(s, "ansi(").and_then(|n| n.strip_suffix(')')) {
+        return n.trim().parse().ok().map(|n| AnyColor::Ansi(AnsiValue(n)));
+    }
+
+    let name: String = s.chars()
+        .filter(|&c| c != ' ' && c != '-' && c != '_')
+        .flat_map(char::to_lowercase)
+        .collect();
+    let color = match &name[..] {
+        "black" => AnyColor::Black,
+        "red" => AnyColor::Red,
+        "green" => AnyColor::Green,
+        "yellow" => AnyColor::Yellow,
+        "blue" => AnyColor::Blue,
+        "magenta" => AnyColor::Magenta,
+        "cyan" => AnyColor::Cyan,
+        "white" => AnyColor::White,
+        "lightblack" => AnyColor::LightBlack,
+        "lightred" => AnyColor::LightRed,
+        "lightgreen" => AnyColor::LightGreen,
+        "lightyellow" => AnyColor::LightYellow,
+        "lightblue" => AnyColor::LightBlue,
+        "lightmagenta" => AnyColor::LightMagenta,
+        "lightcyan" => AnyColor::LightCyan,
+        "lightwhite" => AnyColor::LightWhite,
+        "reset" => AnyColor::Reset,
+        name => {
+            let i = x11::COLORS.binary_search_by(|&(x11, _)| x11.cmp(name)).ok()?;
+            AnyColor::Rgb(x11::COLORS[i].1)
+        }
+    };
+    Some(color)
+}
+
+/// The values of hexadecimal digits.
+fn decode_hex_digits(hex: &str) -> Option<Vec<u8>> {
+    hex.chars().map(|c| c.to_digit(16).map(|d| d as u8)).collect()
+}
+
+/// Scale a component of one to four hexadecimal digits, as in `rgb:f/80/ffff`, to 0–255.
+fn scale_hex(hex: &str) -> Option<u8> {
+    if hex.is_empty() || hex.len() > 4 {
+        return None;
+    }
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    let max = (1 << (4 * hex.len())) - 1;
+    Some(((value * 255 + max / 2) / max) as u8)
+}
+
 /// A foreground color.
 #[derive(Debug, Clone, Copy)]
 pub struct Fg<C: Color>(pub C);
@@ -174,12 +531,398 @@ impl<C: Color> fmt::Display for Bg<C> {
     }
 }
 
+/// How many colors a terminal can show.
+///
+/// The depths are ordered, from `NoColor` to `TrueColor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorDepth {
+    /// No colors at all.
+    NoColor,
+    /// The 16 named colors, from `Black` to `LightWhite`.
+    Ansi16,
+    /// The 256 colors of the `AnsiValue` palette.
+    Ansi256,
+    /// Any `Rgb` color.
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Guess the colors of the terminal from the environment variables.
+    ///
+    /// `NO_COLOR` (when not empty) gives `NoColor`, `COLORTERM=truecolor` or `24bit` gives
+    /// `TrueColor`, and otherwise the suffix of `TERM` decides: `-direct`, `-256color`, or `-mono`
+    /// for instance.
+    pub fn from_env() -> ColorDepth {
+        let var = |name: &str| env::var(name).unwrap_or_default();
+        from_vars(&var("TERM"), &var("COLORTERM"), !var("NO_COLOR").is_empty())
+    }
+
+    /// Guess the colors to use on an output, which gets none unless it is a terminal.
+    pub fn detect<T: AsRawFd>(output: &T) -> ColorDepth {
+        if is_tty(output) {
+            ColorDepth::from_env()
+        } else {
+            ColorDepth::NoColor
+        }
+    }
+
+    /// Guess the colors of the terminal from the environment variables, and from its answers,
+    /// read from `reader`.
+    ///
+    /// The terminal is asked for its `RGB` and `colors` capabilities (with XTGETTCAP), and
+    /// whether it keeps a truecolor foreground (with DECRQSS), which resets the text attributes.
+    /// Its answers can only raise the guess, and a terminal which does not answer within
+    /// `timeout` keeps it. Nothing is asked if `NO_COLOR` is set.
+    ///
+    /// The terminal must be in raw mode. The other input read meanwhile is put back into
+    /// `reader`, see the `query` module.
+    pub fn query<W, R>(writer: &mut W, reader: &mut R, timeout: Duration) -> io::Result<ColorDepth>
+        where W: Write + ?Sized,
+              R: Read + Unread + ?Sized
+    {
+        let guess = ColorDepth::from_env();
+        if !env::var("NO_COLOR").unwrap_or_default().is_empty() {
+            return Ok(guess);
+        }
+
+        Ok(probe_colors(writer, reader, timeout)?.map_or(guess, |depth| guess.max(depth)))
+    }
+}
+
+/// Ask the terminal for its colors, as `ColorDepth::query` does.
+fn probe_colors<W, R>(writer: &mut W, reader: &mut R, timeout: Duration)
+                      -> io::Result<Option<ColorDepth>>
+    where W: Write + ?Sized,
+          R: Read + Unread + ?Sized
+{
+    // XTGETTCAP is `DCS + q Pt ST`, with the hexadecimal names of the capabilities, answered
+    // with `DCS 1 + r name=value ST`, or `DCS 0 + r ST` for the unknown ones.
+    // DECRQSS is `DCS $ q m ST`, answered with `DCS 1 $ r Ps m ST` and the current SGR.
+    // DA1 is asked last, as in `identity`, so that unanswered requests do not wait for the
+    // timeout.
+    let request = "\x1BP+q524742;636f6c6f7273\x1B\\\x1B[38;2;1;2;3m\x1BP$qm\x1B\\\x1B[m\x1B[c";
+    let res = query_many(writer, reader, request, timeout, |input| {
+        let is_param = |b: u8| b.is_ascii_digit() || b == b';';
+        let (da1, _) = find_sequence(input, b"\x1B[?", is_param, b"c")?;
+
+        let mut depth = None;
+        let mut ranges = Vec::new();
+        let mut from = 0;
+        while let Some((range, content)) = find_string(&input[from..da1.start], b"\x1BP") {
+            let content = &input[from + content.start..from + content.end];
+            depth = depth.max(parse_capabilities(content).or_else(|| parse_sgr(content)));
+            ranges.push(from + range.start..from + range.end);
+            from += range.end;
+        }
+        ranges.push(da1);
+
+        Some((Ok(depth), ranges))
+    });
+
+    match res.map_err(Error::from) {
+        Ok(depth) => Ok(depth),
+        Err(Error::QueryTimeout { .. }) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Guess the colors from the values of `TERM` and `COLORTERM`, and whether `NO_COLOR` is set.
+pub(crate) fn from_vars(term: &str, colorterm: &str, no_color: bool) -> ColorDepth {
+    if no_color || term.is_empty() || term == "dumb" || term.ends_with("-mono") ||
+       term.ends_with("-m") {
+        ColorDepth::NoColor
+    } else if colorterm == "truecolor" || colorterm == "24bit" || term.ends_with("-direct") {
+        ColorDepth::TrueColor
+    } else if term.ends_with("-256color") {
+        ColorDepth::Ansi256
+    } else {
+        ColorDepth::Ansi16
+    }
+}
+
+/// Decode hexadecimal text, as used by XTGETTCAP.
+fn decode_hex(hex: &[u8]) -> Option<String> {
+    let bytes = hex.chunks(2)
+        .map(|pair| match str::from_utf8(pair) {
+            Ok(s) if s.len() == 2 => u8::from_str_radix(s, 16).ok(),
+            _ => None,
+        })
+        .collect::<Option<Vec<u8>>>()?;
+    String::from_utf8(bytes).ok()
+}
+
+/// Get the colors from an XTGETTCAP answer.
+fn parse_capabilities(content: &[u8]) -> Option<ColorDepth> {
+    let caps = content.strip_prefix(b"1+r")?;
+
+    caps.split(|&b| b == b';')
+        .filter_map(|cap| {
+            let mut parts = cap.splitn(2, |&b| b == b'=');
+            let name = decode_hex(parts.next()?)?;
+            let value = parts.next().and_then(decode_hex);
+
+            match (&name[..], value) {
+                ("RGB", _) => Some(ColorDepth::TrueColor),
+                ("colors", Some(value)) | ("Co", Some(value)) => {
+                    match value.parse::<u32>().ok()? {
+                        n if n >= 1 << 24 => Some(ColorDepth::TrueColor),
+                        n if n >= 256 => Some(ColorDepth::Ansi256),
+                        n if n >= 8 => Some(ColorDepth::Ansi16),
+                        _ => Some(ColorDepth::NoColor),
+                    }
+                }
+                _ => None,
+            }
+        })
+        .max()
+}
+
+/// Get the colors from a DECRQSS answer, after the foreground was set to `Rgb(1, 2, 3)`.
+fn parse_sgr(content: &[u8]) -> Option<ColorDepth> {
+    let sgr = str::from_utf8(content.strip_prefix(b"1$r")?.strip_suffix(b"m")?).ok()?;
+    let sgr = sgr.replace(':', ";");
+
+    if sgr.contains("38;2;1;2;3") || sgr.contains("38;2;;1;2;3") {
+        Some(ColorDepth::TrueColor)
+    } else if sgr.contains("38;5;") {
+        Some(ColorDepth::Ansi256)
+    } else {
+        None
+    }
+}
+
+/// A writer which lowers the colors written through it to a color depth.
+///
+/// The colors of the SGR sequences, such as those of `Fg` and `Bg`, are replaced with the closest
+/// ones available: `Rgb` colors become `AnsiValue` colors of the 6×6×6 cube or grayscale ramp
+/// with `ColorDepth::Ansi256`, and any color becomes one of the 16 base colors with
+/// `ColorDepth::Ansi16`. With `ColorDepth::NoColor`, the colors are removed.
+///
+/// # Example
+///
+/// ```rust
+/// use termion::color::{self, ColorDepth, DownsampleWriter};
+/// use std::io::Write;
+///
+/// let mut out = DownsampleWriter::new(Vec::new(), ColorDepth::Ansi256);
+/// write!(out, "{}Orange", color::Fg(color::Rgb(255, 135, 0))).unwrap();
+/// assert_eq!(out.into_inner().unwrap(), b"\x1B[38;5;208mOrange");
+/// ```
+///
+/// A sequence cut between two writes is held back until its end is written, and written as it
+/// is on `flush`, or once longer than a color sequence can be.
+pub struct DownsampleWriter<W: Write> {
+    output: W,
+    depth: ColorDepth,
+    /// An escape sequence cut between two writes.
+    pending: Vec<u8>,
+}
+
+impl<W: Write> DownsampleWriter<W> {
+    /// Wrap a writer, lowering its colors to `depth`.
+    pub fn new(output: W, depth: ColorDepth) -> DownsampleWriter<W> {
+        DownsampleWriter {
+            output,
+            depth,
+            pending: Vec::new(),
+        }
+    }
+
+    /// The color depth of the output.
+    pub fn depth(&self) -> ColorDepth {
+        self.depth
+    }
+
+    /// Write the incomplete escape sequence held back, if any, as it is.
+    fn write_pending(&mut self) -> io::Result<()> {
+        let pending = ::std::mem::take(&mut self.pending);
+        self.output.write_all(&pending)
+    }
+
+    /// Get the inner writer back, after writing an incomplete escape sequence as it is.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.write_pending()?;
+        Ok(self.output)
+    }
+}
+
+/// The length of the longest CSI sequence held back until its end is written.
+const MAX_PENDING_CSI: usize = 64;
+
+impl<W: Write> Write for DownsampleWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.depth == ColorDepth::TrueColor {
+            self.write_pending()?;
+            self.output.write_all(buf)?;
+            return Ok(buf.len());
+        }
+
+        let mut out = Vec::with_capacity(buf.len());
+        let mut input = ::std::mem::take(&mut self.pending);
+        input.extend_from_slice(buf);
+
+        let mut i = 0;
+        while i < input.len() {
+            if !input[i..].starts_with(b"\x1B[") {
+                // Keep a lone ESC, which may start a sequence in the next write.
+                if input[i] == b'\x1B' && i + 1 == input.len() {
+                    break;
+                }
+                out.push(input[i]);
+                i += 1;
+                continue;
+            }
+
+            // A CSI sequence, ended by a byte from `@` to `~`.
+            let start = i;
+            let end = match input[i + 2..].iter().position(|&b| (0x40..=0x7E).contains(&b)) {
+                Some(len) => i + 2 + len,
+                // Too long to be a color, or not a sequence at all: pass it on.
+                None if input.len() - start > MAX_PENDING_CSI => {
+                    out.extend_from_slice(&input[start..]);
+                    i = input.len();
+                    break;
+                }
+                None => break,
+            };
+            i = end + 1;
+            if input[end] == b'm' {
+                out.extend(downsample_sgr(&input[start + 2..end], self.depth));
+            } else {
+                out.extend_from_slice(&input[start..i]);
+            }
+        }
+        self.pending = input[i..].to_vec();
+
+        self.output.write_all(&out)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_pending()?;
+        self.output.flush()
+    }
+}
+
+/// Rewrite the parameters of an SGR sequence with the colors available at `depth`, and return
+/// the whole sequence.
+fn downsample_sgr(params: &[u8], depth: ColorDepth) -> Vec<u8> {
+    let text = match str::from_utf8(params) {
+        Ok(text) if !text.is_empty() => text,
+        // `CSI m` resets everything, and is kept as is with anything unexpected.
+        _ => return [b"\x1B[", params, b"m"].concat(),
+    };
+
+    let params: Vec<&str> = text.split(';').collect();
+    let mut out: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < params.len() {
+        let param = params[i];
+        // The color, with the base of its parameter: 30 for the foreground, 40 for the
+        // background. The parameters it spans are kept as they are if it is malformed.
+        let (color, len) = match param {
+            "38" | "48" => {
+                let base = if param == "38" { 30 } else { 40 };
+                let (color, len) = match params.get(i + 1).cloned() {
+                    Some("5") => {
+                        let n = params.get(i + 2).and_then(|n| n.parse().ok());
+                        (n.map(AnsiValue).map(Ok), 3)
+                    }
+                    Some("2") => {
+                        let rgb: Vec<u8> = params[i + 2..]
+                            .iter()
+                            .take(3)
+                            .filter_map(|n| n.parse().ok())
+                            .collect();
+                        match rgb[..] {
+                            [r, g, b] => (Some(Err(Rgb(r, g, b))), 5),
+                            _ => (None, 5),
+                        }
+                    }
+                    // The meaning of the parameters which follow is unknown, so the whole
+                    // sequence is kept as is.
+                    _ => return [b"\x1B[", text.as_bytes(), b"m"].concat(),
+                };
+                (color.map(|color| (base, color)), len)
+            }
+            // The colon form, such as `38:2::255:0:0` or `38:5:208`.
+            _ if param.starts_with("38:") || param.starts_with("48:") => {
+                let base = if param.starts_with("38:") { 30 } else { 40 };
+                let sub: Vec<&str> = param.split(':').collect();
+                let color = match (sub.get(1).cloned(), sub.len()) {
+                    (Some("5"), 3) => sub[2].parse().ok().map(AnsiValue).map(Ok),
+                    (Some("2"), 5) | (Some("2"), 6) => {
+                        let rgb = &sub[sub.len() - 3..];
+                        match (rgb[0].parse(), rgb[1].parse(), rgb[2].parse()) {
+                            (Ok(r), Ok(g), Ok(b)) => Some(Err(Rgb(r, g, b))),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                };
+                (color.map(|color| (base, color)), 1)
+            }
+            // The 16 base colors, and the default colors, are only removed.
+            _ => {
+                match param.parse::<u8>() {
+                    Ok(30..=39) | Ok(40..=49) | Ok(90..=97) | Ok(100..=107)
+                        if depth == ColorDepth::NoColor => {
+                        i += 1;
+                        continue;
+                    }
+                    _ => (None, 1),
+                }
+            }
+        };
+
+        let end = (i + len).min(params.len());
+        let (base, color) = match color {
+            Some(color) => color,
+            None => {
+                out.extend(params[i..end].iter().map(|param| param.to_string()));
+                i = end;
+                continue;
+            }
+        };
+        i = end;
+
+        let value = match (depth, color) {
+            (ColorDepth::NoColor, _) => continue,
+            (ColorDepth::TrueColor, Err(rgb)) => {
+                format!("{};2;{};{};{}", base + 8, rgb.0, rgb.1, rgb.2)
+            }
+            (ColorDepth::TrueColor, Ok(ansi)) |
+            (ColorDepth::Ansi256, Ok(ansi)) => ansi_param(base, ansi, true),
+            (ColorDepth::Ansi256, Err(rgb)) => ansi_param(base, rgb.to_ansi256(), true),
+            (ColorDepth::Ansi16, Ok(ansi)) => ansi_param(base, ansi.to_ansi16(), false),
+            (ColorDepth::Ansi16, Err(rgb)) => ansi_param(base, rgb.to_ansi16(), false),
+        };
+        out.push(value);
+    }
+
+    if out.is_empty() {
+        // Everything was removed: the sequence must not become a reset.
+        return Vec::new();
+    }
+    format!("\x1B[{}m", out.join(";")).into_bytes()
+}
+
+/// The SGR parameter of an `AnsiValue` color, where `base` is 30 for the foreground and 40 for
+/// the background. The 16 base colors keep their own parameters unless `indexed` is set.
+fn ansi_param(base: u8, color: AnsiValue, indexed: bool) -> String {
+    match color.0 {
+        n @ 0..=7 if !indexed => (base + n).to_string(),
+        n @ 8..=15 if !indexed => (base + 60 + n - 8).to_string(),
+        n => format!("{};5;{}", base + 8, n),
+    }
+}
+
 /// Types that allow detection of the colors they support.
 pub trait DetectColors {
     /// How many ANSI colors are supported (from 8 to 256)?
     ///
     /// Beware: the information given isn't authoritative, it's infered through escape codes or the
-    /// value of `TERM`, more colors may be available.
+    /// value of `TERM`, more colors may be available. See `ColorDepth` to also detect truecolor.
     fn available_colors(&mut self) -> io::Result<u16>;
 }
 
@@ -205,38 +948,704 @@ impl<W: Write> DetectColors for W {
         } else {
             // OSC 4 is not supported, trust TERM contents.
             Ok(match env::var_os("TERM") {
-                   Some(val) => {
-                       if val.to_str().unwrap_or("").contains("256color") {
-                           256
-                       } else {
-                           8
-                       }
-                   }
-                   None => 8,
+                   Some(ref val) if val.to_str().unwrap_or("").contains("256color") => 256,
+                   _ => 8,
                })
         }
     }
 }
 
 /// Detect a color using OSC 4.
-fn detect_color(stdout: &mut Write, stdin: &mut Read, color: u16) -> io::Result<bool> {
+fn detect_color<W, R>(stdout: &mut W, stdin: &mut R, color: u16) -> io::Result<bool>
+    where W: Write + ?Sized,
+          R: Read + Unread + ?Sized
+{
     // Is the color available?
-    // Use `ESC ] 4 ; color ; ? BEL`.
-    write!(stdout, "\x1B]4;{};?\x07", color)?;
-    stdout.flush()?;
+    // Use `ESC ] 4 ; color ; ? BEL`, answered with `ESC ] 4 ; color ; rgb:... BEL`.
+    let request = format!("\x1B]4;{};?\x07", color);
+    let res = query(stdout, stdin, &request, DEFAULT_TIMEOUT, |input| {
+        find_string(input, b"\x1B]4;").map(|(range, _)| (Ok(()), range))
+    });
+
+    // If there was a response, the color is supported.
+    match res.map_err(Error::from) {
+        Ok(()) => Ok(true),
+        Err(Error::QueryTimeout { .. }) => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
 
-    let mut buf: [u8; 1] = [0];
-    let mut total_read = 0;
+/// Ask the terminal for its default foreground color (with OSC 10), reading the answer from
+/// `reader`.
+///
+/// See `query_palette_color`.
+pub fn query_foreground_color<W, R>(writer: &mut W, reader: &mut R, timeout: Duration)
+                                    -> io::Result<Option<Rgb>>
+    where W: Write + ?Sized,
+          R: Read + Unread + ?Sized
+{
+    query_osc_color(writer, reader, "10", timeout)
+}
 
-    let timeout = Duration::from_millis(CONTROL_SEQUENCE_TIMEOUT);
-    let now = SystemTime::now();
-    let bell = 7u8;
+/// Ask the terminal for its default background color (with OSC 11), reading the answer from
+/// `reader`.
+///
+/// See `query_palette_color`.
+pub fn query_background_color<W, R>(writer: &mut W, reader: &mut R, timeout: Duration)
+                                    -> io::Result<Option<Rgb>>
+    where W: Write + ?Sized,
+          R: Read + Unread + ?Sized
+{
+    query_osc_color(writer, reader, "11", timeout)
+}
 
-    // Either consume all data up to bell or wait for a timeout.
-    while buf[0] != bell && now.elapsed().unwrap() < timeout {
-        total_read += stdin.read(&mut buf)?;
+/// Ask the terminal for the value of a color of its palette (with OSC 4), reading the answer
+/// from `reader`.
+///
+/// This returns `None` if the terminal does not tell, without waiting for the timeout, unless it
+/// does not answer the Primary Device Attributes either: this fails with `Error::QueryTimeout`.
+///
+/// The terminal must be in raw mode. The other input read meanwhile is put back into `reader`,
+/// see the `query` module.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use termion::async_stdin;
+/// use termion::color::{self, query_palette_color};
+/// use termion::query::DEFAULT_TIMEOUT;
+/// use termion::raw::IntoRawMode;
+/// use std::io::stdout;
+///
+/// fn main() {
+///     let mut stdout = stdout().into_raw_mode().unwrap();
+///     let mut stdin = async_stdin();
+///
+///     let red = color::AnsiValue::from(color::Red);
+///     let rgb = query_palette_color(&mut stdout, &mut stdin, red, DEFAULT_TIMEOUT).unwrap();
+///     println!("Red is {:?}.\r", rgb);
+/// }
+/// ```
+pub fn query_palette_color<W, R>(writer: &mut W,
+                                 reader: &mut R,
+                                 color: AnsiValue,
+                                 timeout: Duration)
+                                 -> io::Result<Option<Rgb>>
+    where W: Write + ?Sized,
+          R: Read + Unread + ?Sized
+{
+    query_osc_color(writer, reader, &format!("4;{}", color.0), timeout)
+}
+
+/// Check whether the default background color of the terminal is dark, reading the answer from
+/// `reader`.
+///
+/// This returns `None` if the terminal does not tell its background color. See
+/// `query_background_color`.
+pub fn is_dark_background<W, R>(writer: &mut W, reader: &mut R, timeout: Duration)
+                                -> io::Result<Option<bool>>
+    where W: Write + ?Sized,
+          R: Read + Unread + ?Sized
+{
+    Ok(query_background_color(writer, reader, timeout)?.map(is_dark))
+}
+
+/// Check whether a color is dark, that is closer to black than to white to the eye.
+fn is_dark(color: Rgb) -> bool {
+    let (lightness, _, _) = to_lab(color);
+    lightness < 50.0
+}
+
+/// Ask for a color with `OSC Ps ; ? BEL`, where `target` is `Ps` (such as `10`, or `4;1` for a
+/// color of the palette).
+fn query_osc_color<W, R>(writer: &mut W, reader: &mut R, target: &str, timeout: Duration)
+                         -> io::Result<Option<Rgb>>
+    where W: Write + ?Sized,
+          R: Read + Unread + ?Sized
+{
+    query_osc_colors(writer, reader, &[target.to_owned()], timeout).map(|colors| colors[0])
+}
+
+/// Ask for colors like `query_osc_color`, at once.
+fn query_osc_colors<W, R>(writer: &mut W, reader: &mut R, targets: &[String], timeout: Duration)
+                          -> io::Result<Vec<Option<Rgb>>>
+    where W: Write + ?Sized,
+          R: Read + Unread + ?Sized
+{
+    let specs = query_osc_specs(writer, reader, targets, timeout)?;
+    let colors = specs.iter()
+        .map(|spec| spec.as_ref().and_then(|spec| parse_osc_color(spec.as_bytes())))
+        .collect();
+    Ok(colors)
+}
+
+/// Ask for colors like `query_osc_colors`, getting their specifications as the terminal wrote
+/// them, such as `rgb:ffff/8080/0000`.
+fn query_osc_specs<W, R>(writer: &mut W, reader: &mut R, targets: &[String], timeout: Duration)
+                         -> io::Result<Vec<Option<String>>>
+    where W: Write + ?Sized,
+          R: Read + Unread + ?Sized
+{
+    // The answer is `OSC Ps ; rgb:rrrr/gggg/bbbb ST`. DA1 is asked last, as in `identity`, so
+    // that a terminal which does not answer is not waited for.
+    let mut request = String::new();
+    for target in targets {
+        request.push_str(&format!("\x1B]{};?\x07", target));
     }
+    request.push_str("\x1B[c");
 
-    // If there was a response, the color is supported.
-    Ok(total_read > 0)
+    query_many(writer, reader, &request, timeout, |input| {
+        let is_param = |b: u8| b.is_ascii_digit() || b == b';';
+        let (da1, _) = find_sequence(input, b"\x1B[?", is_param, b"c")?;
+
+        let mut specs = Vec::with_capacity(targets.len());
+        let mut ranges = Vec::new();
+        let mut malformed = None;
+        for target in targets {
+            let intro = format!("\x1B]{};", target);
+            match find_string(&input[..da1.start], intro.as_bytes()) {
+                Some((range, content)) => {
+                    let spec = &input[content];
+                    if parse_osc_color(spec).is_some() {
+                        // Valid specifications are ASCII.
+                        specs.push(Some(String::from_utf8_lossy(spec).into_owned()));
+                    } else {
+                        if malformed.is_none() {
+                            malformed = Some(input[range.clone()].to_vec());
+                        }
+                        specs.push(None);
+                    }
+                    ranges.push(range);
+                }
+                None => specs.push(None),
+            }
+        }
+        // The same color may be asked twice.
+        ranges.sort_by_key(|range| range.start);
+        ranges.dedup();
+        ranges.push(da1);
+
+        match malformed {
+            Some(bytes) => Some((Err(Error::MalformedResponse { bytes }), ranges)),
+            None => Some((Ok(specs), ranges)),
+        }
+    })
+}
+
+/// Parse the color of an OSC answer, `rgb:rrrr/gggg/bbbb` (or `rgba:rrrr/gggg/bbbb/aaaa`, as
+/// sent by rxvt-unicode).
+fn parse_osc_color(content: &[u8]) -> Option<Rgb> {
+    let content = str::from_utf8(content).ok()?;
+    let (spec, len) = match content.strip_prefix("rgba:") {
+        Some(spec) => (spec, 4),
+        None => (content.strip_prefix("rgb:")?, 3),
+    };
+
+    let components = spec.split('/').map(scale_hex).collect::<Option<Vec<u8>>>()?;
+    if components.len() == len {
+        Some(Rgb(components[0], components[1], components[2]))
+    } else {
+        None
+    }
+}
+
+/// Write `rgb:rr/gg/bb`, the color syntax of the OSC sequences.
+fn write_osc_rgb(f: &mut fmt::Formatter, color: Rgb) -> fmt::Result {
+    write!(f, "rgb:{:02x}/{:02x}/{:02x}", color.0, color.1, color.2)
+}
+
+macro_rules! derive_osc_color_sequence {
+    ($($doc:expr, $name:ident, $value:expr;)*) => {
+        $(
+            #[doc = $doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            pub struct $name(pub Rgb);
+
+            impl fmt::Display for $name {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    write!(f, concat!("\x1B]", $value, ";"))?;
+                    write_osc_rgb(f, self.0)?;
+                    write!(f, "\x07")
+                }
+            }
+        )*
+    };
+}
+
+derive_osc_color_sequence! {
+    "Set the default foreground color of the terminal (with OSC 10).", SetForegroundColor, "10";
+    "Set the default background color of the terminal (with OSC 11).", SetBackgroundColor, "11";
+    "Set the color of the cursor (with OSC 12).", SetCursorColor, "12";
+}
+
+/// Set the value of a color of the palette of the terminal (with OSC 4).
+///
+/// It changes the text already written with the color, as well as the text to come.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetPaletteColor(pub AnsiValue, pub Rgb);
+
+impl fmt::Display for SetPaletteColor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\x1B]4;{};", (self.0).0)?;
+        write_osc_rgb(f, self.1)?;
+        write!(f, "\x07")
+    }
+}
+
+/// Reset a color of the palette of the terminal to its initial value (with OSC 104).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResetPaletteColor(pub AnsiValue);
+
+impl fmt::Display for ResetPaletteColor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\x1B]104;{}\x07", (self.0).0)
+    }
+}
+
+macro_rules! derive_osc_reset_sequence {
+    ($($doc:expr, $name:ident, $value:expr;)*) => {
+        $(
+            #[doc = $doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            pub struct $name;
+
+            impl fmt::Display for $name {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    write!(f, concat!("\x1B]", $value, "\x07"))
+                }
+            }
+        )*
+    };
+}
+
+derive_osc_reset_sequence! {
+    "Reset the whole palette of the terminal to its initial values (with OSC 104).", ResetPalette,
+    "104";
+    "Reset the default foreground color of the terminal (with OSC 110).", ResetForegroundColor,
+    "110";
+    "Reset the default background color of the terminal (with OSC 111).", ResetBackgroundColor,
+    "111";
+    "Reset the color of the cursor (with OSC 112).", ResetCursorColor, "112";
+}
+
+/// A wrapper restoring the colors of the terminal when dropped.
+///
+/// The default foreground, background and cursor colors, and the given colors of the palette,
+/// are asked to the terminal on creation, and set back on drop, exactly as the terminal wrote
+/// them. The colors the terminal does not tell are reset to their initial values instead.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use termion::async_stdin;
+/// use termion::color::{self, ColorGuard, SetBackgroundColor, SetPaletteColor};
+/// use termion::query::DEFAULT_TIMEOUT;
+/// use termion::raw::IntoRawMode;
+/// use std::io::{Write, stdout};
+///
+/// fn main() {
+///     let stdout = stdout().into_raw_mode().unwrap();
+///     let mut stdin = async_stdin();
+///     let red = color::AnsiValue::from(color::Red);
+///
+///     let mut out = ColorGuard::new(stdout, &mut stdin, &[red], DEFAULT_TIMEOUT).unwrap();
+///     write!(out, "{}", SetBackgroundColor(color::Rgb(0x28, 0x2c, 0x34))).unwrap();
+///     write!(out, "{}", SetPaletteColor(red, color::Rgb(0xe0, 0x6c, 0x75))).unwrap();
+///     // The colors are restored here.
+/// }
+/// ```
+pub struct ColorGuard<W: Write> {
+    /// The output, taken by `restore`.
+    output: Option<W>,
+    /// The specifications of the colors, such as `rgb:ffff/8080/0000`, as the terminal wrote them.
+    foreground: Option<String>,
+    background: Option<String>,
+    cursor: Option<String>,
+    palette: Vec<(AnsiValue, Option<String>)>,
+}
+
+impl<W: Write> ColorGuard<W> {
+    /// Wrap a writer, asking the terminal for its colors, and for those of `palette`, through it.
+    ///
+    /// The answers are read from `reader`, and the other input read meanwhile is put back into
+    /// it, see the `query` module. The terminal must be in raw mode.
+    pub fn new<R>(mut output: W, reader: &mut R, palette: &[AnsiValue], timeout: Duration)
+                  -> io::Result<ColorGuard<W>>
+        where R: Read + Unread + ?Sized
+    {
+        let mut palette = palette.to_vec();
+        palette.sort_by_key(|color| color.0);
+        palette.dedup();
+
+        let mut targets = vec!["10".to_owned(), "11".to_owned(), "12".to_owned()];
+        targets.extend(palette.iter().map(|color| format!("4;{}", color.0)));
+
+        let mut specs = match query_osc_specs(&mut output, reader, &targets, timeout) {
+            Ok(specs) => specs,
+            // The colors are reset instead.
+            Err(err) => {
+                match Error::from(err) {
+                    Error::QueryTimeout { .. } => vec![None; targets.len()],
+                    err => return Err(err.into()),
+                }
+            }
+        };
+
+        let palette_specs = specs.split_off(3);
+        let mut specs = specs.into_iter();
+        Ok(ColorGuard {
+            output: Some(output),
+            foreground: specs.next().unwrap_or(None),
+            background: specs.next().unwrap_or(None),
+            cursor: specs.next().unwrap_or(None),
+            palette: palette.into_iter().zip(palette_specs).collect(),
+        })
+    }
+
+    /// The sequences restoring the colors.
+    fn restore_sequences(&self) -> String {
+        let mut out = String::new();
+        match self.foreground {
+            Some(ref spec) => out.push_str(&format!("\x1B]10;{}\x07", spec)),
+            None => out.push_str(&ResetForegroundColor.to_string()),
+        }
+        match self.background {
+            Some(ref spec) => out.push_str(&format!("\x1B]11;{}\x07", spec)),
+            None => out.push_str(&ResetBackgroundColor.to_string()),
+        }
+        match self.cursor {
+            Some(ref spec) => out.push_str(&format!("\x1B]12;{}\x07", spec)),
+            None => out.push_str(&ResetCursorColor.to_string()),
+        }
+        for &(index, ref spec) in &self.palette {
+            match *spec {
+                Some(ref spec) => out.push_str(&format!("\x1B]4;{};{}\x07", index.0, spec)),
+                None => out.push_str(&ResetPaletteColor(index).to_string()),
+            }
+        }
+        out
+    }
+
+    /// Restore the colors, and get the inner writer back.
+    ///
+    /// Unlike dropping the wrapper, this flushes the output and reports failures, in which case
+    /// the writer is dropped.
+    pub fn restore(mut self) -> io::Result<W> {
+        let sequences = self.restore_sequences();
+        let mut output = self.output.take().expect("the output is only taken when restoring");
+        output.write_all(sequences.as_bytes())?;
+        output.flush()?;
+        Ok(output)
+    }
+}
+
+impl<W: Write> Drop for ColorGuard<W> {
+    fn drop(&mut self) {
+        // Errors cannot be reported from here; `restore` does that.
+        let sequences = self.restore_sequences();
+        if let Some(ref mut output) = self.output {
+            let _ = output.write_all(sequences.as_bytes());
+            let _ = output.flush();
+        }
+    }
+}
+
+impl<W: Write> ops::Deref for ColorGuard<W> {
+    type Target = W;
+
+    fn deref(&self) -> &W {
+        self.output.as_ref().expect("the output is only taken when restoring")
+    }
+}
+
+impl<W: Write> ops::DerefMut for ColorGuard<W> {
+    fn deref_mut(&mut self) -> &mut W {
+        self.output.as_mut().expect("the output is only taken when restoring")
+    }
+}
+
+impl<W: Write> Write for ColorGuard<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (**self).write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        (**self).flush()
+    }
+}
+
+impl<W: Write + AsRawFd> AsRawFd for ColorGuard<W> {
+    fn as_raw_fd(&self) -> RawFd {
+        (**self).as_raw_fd()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use query::Pushback;
+
+    #[test]
+    fn test_sequences() {
+        fn check<C: Color + Copy>(color: C, fg: &str, bg: &str) {
+            assert_eq!(Fg(color).to_string(), fg);
+            assert_eq!(Bg(color).to_string(), bg);
+        }
+
+        check(Black, "\x1B[30m", "\x1B[40m");
+        check(Red, "\x1B[31m", "\x1B[41m");
+        check(Green, "\x1B[32m", "\x1B[42m");
+        check(Yellow, "\x1B[33m", "\x1B[43m");
+        check(Blue, "\x1B[34m", "\x1B[44m");
+        check(Magenta, "\x1B[35m", "\x1B[45m");
+        check(Cyan, "\x1B[36m", "\x1B[46m");
+        check(White, "\x1B[37m", "\x1B[47m");
+        check(LightBlack, "\x1B[90m", "\x1B[100m");
+        check(LightRed, "\x1B[91m", "\x1B[101m");
+        check(LightGreen, "\x1B[92m", "\x1B[102m");
+        check(LightYellow, "\x1B[93m", "\x1B[103m");
+        check(LightBlue, "\x1B[94m", "\x1B[104m");
+        check(LightMagenta, "\x1B[95m", "\x1B[105m");
+        check(LightCyan, "\x1B[96m", "\x1B[106m");
+        check(LightWhite, "\x1B[97m", "\x1B[107m");
+        check(AnsiValue(208), "\x1B[38;5;208m", "\x1B[48;5;208m");
+        check(AnsiValue::from(LightRed), "\x1B[38;5;9m", "\x1B[48;5;9m");
+        check(Rgb(1, 2, 3), "\x1B[38;2;1;2;3m", "\x1B[48;2;1;2;3m");
+        check(Reset, "\x1B[39m", "\x1B[49m");
+        check(AnyColor::Cyan, "\x1B[36m", "\x1B[46m");
+        check(AnyColor::Ansi(AnsiValue(7)), "\x1B[38;5;7m", "\x1B[48;5;7m");
+        check(&Red as &dyn Color, "\x1B[31m", "\x1B[41m");
+    }
+
+    #[test]
+    fn test_from_vars() {
+        assert_eq!(from_vars("xterm-256color", "truecolor", false), ColorDepth::TrueColor);
+        assert_eq!(from_vars("xterm-256color", "", false), ColorDepth::Ansi256);
+        assert_eq!(from_vars("xterm-direct", "", false), ColorDepth::TrueColor);
+        assert_eq!(from_vars("vt100", "", false), ColorDepth::Ansi16);
+        assert_eq!(from_vars("xterm-mono", "", false), ColorDepth::NoColor);
+        assert_eq!(from_vars("dumb", "24bit", false), ColorDepth::NoColor);
+        assert_eq!(from_vars("xterm-256color", "truecolor", true), ColorDepth::NoColor);
+    }
+
+    #[test]
+    fn test_parse_answers() {
+        // RGB=8/8/8, and colors=256.
+        assert_eq!(parse_capabilities(b"1+r524742=382f382f38"), Some(ColorDepth::TrueColor));
+        assert_eq!(parse_capabilities(b"1+r636f6c6f7273=323536"), Some(ColorDepth::Ansi256));
+        assert_eq!(parse_capabilities(b"0+r524742"), None);
+
+        assert_eq!(parse_sgr(b"1$r0;38:2::1:2:3m"), Some(ColorDepth::TrueColor));
+        assert_eq!(parse_sgr(b"1$r0;38;5;16m"), Some(ColorDepth::Ansi256));
+        assert_eq!(parse_sgr(b"1$r0m"), None);
+        assert_eq!(parse_sgr(b"0$r"), None);
+    }
+
+    #[test]
+    fn test_query() {
+        let mut written = Vec::new();
+        let mut reader = Pushback::new(&b"a\x1BP0+r524742\x1B\\\x1BP1+r636f6c6f7273=323536\x1B\\\
+                                          \x1BP1$r0;38:2::1:2:3m\x1B\\\x1B[?62;22cb"[..]);
+        let depth = probe_colors(&mut written, &mut reader, DEFAULT_TIMEOUT).unwrap();
+        assert_eq!(depth, Some(ColorDepth::TrueColor));
+        assert!(written.ends_with(b"\x1B[c"));
+
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, b"ab");
+
+        // A terminal without XTGETTCAP nor DECRQSS.
+        let mut reader = Pushback::new(&b"\x1B[?1;2c"[..]);
+        let depth = probe_colors(&mut written, &mut reader, DEFAULT_TIMEOUT).unwrap();
+        assert_eq!(depth, None);
+    }
+
+    #[test]
+    fn test_parse_any_color() {
+        let parse = |s: &str| s.parse::<AnyColor>().unwrap();
+        assert_eq!(parse("#FF8000"), AnyColor::Rgb(Rgb(255, 128, 0)));
+        assert_eq!(parse("#f80"), AnyColor::Rgb(Rgb(255, 136, 0)));
+        assert_eq!(parse("rgb:ff/80/00"), AnyColor::Rgb(Rgb(255, 128, 0)));
+        assert_eq!(parse("rgb:f/8000/0"), AnyColor::Rgb(Rgb(255, 128, 0)));
+        assert_eq!(parse("ansi(208)"), AnyColor::Ansi(AnsiValue(208)));
+        assert_eq!(parse("RGB:FF/80/00"), AnyColor::Rgb(Rgb(255, 128, 0)));
+        assert_eq!(parse("Ansi( 208 )"), AnyColor::Ansi(AnsiValue(208)));
+        assert_eq!(parse("Light Blue"), AnyColor::LightBlue);
+        assert_eq!(parse("light_magenta"), AnyColor::LightMagenta);
+        assert_eq!(parse("red"), AnyColor::Red);
+        assert_eq!(parse("reset"), AnyColor::Reset);
+        assert_eq!(parse("SteelBlue"), AnyColor::Rgb(Rgb(70, 130, 180)));
+        assert_eq!(parse("dark olive green"), AnyColor::Rgb(Rgb(85, 107, 47)));
+        assert_eq!(parse("grey50"), AnyColor::Rgb(Rgb(127, 127, 127)));
+
+        for s in &["", "#12345", "#ggg", "rgb:1/2", "rgb:12345/0/0", "ansi(256)", "redd"] {
+            match s.parse::<AnyColor>() {
+                Err(Error::InvalidColor { text }) => assert_eq!(&text, s),
+                res => panic!("unexpected result for {:?}: {:?}", s, res),
+            }
+        }
+        assert!(x11::COLORS.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn test_any_color() {
+        use std::collections::HashSet;
+
+        assert_eq!(format!("{}", Fg(AnyColor::from(Red))), format!("{}", Fg(Red)));
+        assert_eq!(format!("{}", Bg(AnyColor::from(Rgb(1, 2, 3)))),
+                   format!("{}", Bg(Rgb(1, 2, 3))));
+        assert_eq!(format!("{}", Fg(AnyColor::Reset)), format!("{}", Fg(Reset)));
+
+        let colors: HashSet<AnyColor> = [AnyColor::Red, AnyColor::Ansi(AnsiValue(1)), AnyColor::Red]
+            .iter()
+            .cloned()
+            .collect();
+        assert_eq!(colors.len(), 2);
+    }
+
+    #[test]
+    fn test_query_colors() {
+        let mut written = Vec::new();
+        let mut reader = Pushback::new(&b"a\x1B]11;rgb:ffff/ffff/dddd\x1B\\b\x1B[?62;22cc"[..]);
+        let dark = is_dark_background(&mut written, &mut reader, DEFAULT_TIMEOUT).unwrap();
+        assert_eq!(dark, Some(false));
+        assert_eq!(written, b"\x1B]11;?\x07\x1B[c");
+
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, b"abc");
+
+        let mut reader = Pushback::new(&b"\x1B]10;rgb:2e/34/36\x07\x1B[?1;2c"[..]);
+        let fg = query_foreground_color(&mut written, &mut reader, DEFAULT_TIMEOUT).unwrap();
+        assert_eq!(fg, Some(Rgb(0x2e, 0x34, 0x36)));
+
+        let mut reader = Pushback::new(&b"\x1B]4;9;rgba:ffff/0000/0000/ffff\x07\x1B[?1;2c"[..]);
+        let red = query_palette_color(&mut written, &mut reader, AnsiValue(9), DEFAULT_TIMEOUT);
+        assert_eq!(red.unwrap(), Some(Rgb(255, 0, 0)));
+
+        // A terminal which does not answer OSC 11.
+        let mut reader = Pushback::new(&b"\x1B[?1;2c"[..]);
+        assert_eq!(is_dark_background(&mut written, &mut reader, DEFAULT_TIMEOUT).unwrap(), None);
+
+        let mut reader = Pushback::new(&b"\x1B]11;#000000\x07\x1B[?1;2c"[..]);
+        match Error::from(query_background_color(&mut written, &mut reader, DEFAULT_TIMEOUT)
+                              .unwrap_err()) {
+            Error::MalformedResponse { bytes } => assert_eq!(bytes, b"\x1B]11;#000000\x07"),
+            err => panic!("unexpected error: {:?}", err),
+        }
+
+        assert!(is_dark(Rgb(0x2e, 0x34, 0x36)));
+        assert!(!is_dark(Rgb(0xfd, 0xf6, 0xe3)));
+    }
+
+    #[test]
+    fn test_set_colors() {
+        assert_eq!(SetPaletteColor(AnsiValue(1), Rgb(0xe0, 0x6c, 0x75)).to_string(),
+                   "\x1B]4;1;rgb:e0/6c/75\x07");
+        assert_eq!(SetForegroundColor(Rgb(0, 0, 0)).to_string(), "\x1B]10;rgb:00/00/00\x07");
+        assert_eq!(SetBackgroundColor(Rgb(255, 255, 255)).to_string(),
+                   "\x1B]11;rgb:ff/ff/ff\x07");
+        assert_eq!(SetCursorColor(Rgb(1, 2, 3)).to_string(), "\x1B]12;rgb:01/02/03\x07");
+        assert_eq!(ResetPaletteColor(AnsiValue(1)).to_string(), "\x1B]104;1\x07");
+        assert_eq!(ResetPalette.to_string(), "\x1B]104\x07");
+        assert_eq!(ResetForegroundColor.to_string(), "\x1B]110\x07");
+        assert_eq!(ResetBackgroundColor.to_string(), "\x1B]111\x07");
+        assert_eq!(ResetCursorColor.to_string(), "\x1B]112\x07");
+    }
+
+    #[test]
+    fn test_color_guard() {
+        let mut reader = Pushback::new(&b"\x1B]10;rgb:ffff/ffff/ffff\x07\
+                                          \x1B]11;rgb:0000/0000/0000\x07\
+                                          \x1B]4;1;rgb:cdcd/0000/0000\x07\x1B[?1;2ca"[..]);
+        let palette = [AnsiValue(2), AnsiValue(1), AnsiValue(2)];
+        let mut guard = ColorGuard::new(Vec::new(), &mut reader, &palette, DEFAULT_TIMEOUT)
+            .unwrap();
+        assert_eq!(&guard[..],
+                   &b"\x1B]10;?\x07\x1B]11;?\x07\x1B]12;?\x07\
+                      \x1B]4;1;?\x07\x1B]4;2;?\x07\x1B[c"[..]);
+        guard.clear();
+
+        write!(guard, "{}", SetPaletteColor(AnsiValue(1), Rgb(1, 2, 3))).unwrap();
+        let output = guard.restore().unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(),
+                   "\x1B]4;1;rgb:01/02/03\x07\
+                    \x1B]10;rgb:ffff/ffff/ffff\x07\x1B]11;rgb:0000/0000/0000\x07\x1B]112\x07\
+                    \x1B]4;1;rgb:cdcd/0000/0000\x07\x1B]104;2\x07");
+
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, b"a");
+
+        // A terminal which does not answer at all.
+        let mut reader = Pushback::new(&b""[..]);
+        let timeout = Duration::from_millis(10);
+        let guard = ColorGuard::new(Vec::new(), &mut reader, &[], timeout).unwrap();
+        assert!(guard.restore().unwrap().ends_with(b"\x1B]110\x07\x1B]111\x07\x1B]112\x07"));
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(Rgb(255, 0, 0).to_ansi256(), AnsiValue(196));
+        assert_eq!(Rgb(255, 135, 0).to_ansi256(), AnsiValue(208));
+        assert_eq!(Rgb(128, 128, 128).to_ansi256(), AnsiValue(244));
+        assert_eq!(Rgb(0, 0, 0).to_ansi256(), AnsiValue(16));
+        assert_eq!(Rgb(250, 20, 30).to_ansi16(), AnsiValue(9));
+        assert_eq!(Rgb(20, 20, 20).to_ansi16(), AnsiValue(0));
+
+        assert_eq!(AnsiValue(196).to_rgb(), Rgb(255, 0, 0));
+        assert_eq!(AnsiValue::grayscale(0).to_rgb(), Rgb(8, 8, 8));
+        assert_eq!(AnsiValue(4).to_rgb(), Rgb(0, 0, 238));
+        assert_eq!(AnsiValue(46).to_ansi16(), AnsiValue(10));
+
+        for n in 16..=255 {
+            assert_eq!(AnsiValue(n).to_rgb().to_ansi256(), AnsiValue(n));
+        }
+    }
+
+    fn downsample(depth: ColorDepth, writes: &[&[u8]]) -> Vec<u8> {
+        let mut out = DownsampleWriter::new(Vec::new(), depth);
+        for buf in writes {
+            out.write_all(buf).unwrap();
+        }
+        out.into_inner().unwrap()
+    }
+
+    #[test]
+    fn test_downsample_writer() {
+        let input: &[u8] = b"\x1B[1;38;2;255;0;0ma\x1B[48;5;196;31mb\x1B[38:2::0:0:238m\
+                             \x1B[2Jc\x1B[m";
+
+        assert_eq!(downsample(ColorDepth::TrueColor, &[input]), input);
+        assert_eq!(downsample(ColorDepth::Ansi256, &[input]),
+                   &b"\x1B[1;38;5;196ma\x1B[48;5;196;31mb\x1B[38;5;21m\x1B[2Jc\x1B[m"[..]);
+        assert_eq!(downsample(ColorDepth::Ansi16, &[input]),
+                   &b"\x1B[1;91ma\x1B[101;31mb\x1B[34m\x1B[2Jc\x1B[m"[..]);
+        assert_eq!(downsample(ColorDepth::NoColor, &[input]),
+                   &b"\x1B[1mab\x1B[2Jc\x1B[m"[..]);
+
+        // Sequences cut between writes.
+        assert_eq!(downsample(ColorDepth::Ansi16, &[b"a\x1B", b"[38;2;0;", b"0;0mb"]),
+                   &b"a\x1B[30mb"[..]);
+        // An unfinished sequence is written as it is.
+        assert_eq!(downsample(ColorDepth::Ansi16, &[b"a\x1B[38;2"]), &b"a\x1B[38;2"[..]);
+        let mut out = DownsampleWriter::new(Vec::new(), ColorDepth::Ansi16);
+        out.write_all(b"\x1B[38;5").unwrap();
+        out.flush().unwrap();
+        out.write_all(b";196m").unwrap();
+        assert_eq!(out.into_inner().unwrap(), b"\x1B[38;5;196m");
+        let long = [&b"\x1B["[..], &[b'1'; 100][..]].concat();
+        let mut out = DownsampleWriter::new(Vec::new(), ColorDepth::Ansi16);
+        out.write_all(&long).unwrap();
+        assert_eq!(out.output, long);
+
+        // Malformed colors are kept as they are.
+        assert_eq!(downsample(ColorDepth::Ansi256, &[b"\x1B[38;5;300;1m\x1B[48;2;1;x;3;4m"]),
+                   &b"\x1B[38;5;300;1m\x1B[48;2;1;x;3;4m"[..]);
+        assert_eq!(downsample(ColorDepth::NoColor, &[b"\x1B[38;2;40;41m\x1B[38;7;31m"]),
+                   &b"\x1B[38;2;40;41m\x1B[38;7;31m"[..]);
+        // So is a sequence with an unknown color mode, whose parameters cannot be told apart.
+        assert_eq!(downsample(ColorDepth::NoColor, &[b"\x1B[1;31;48;7;31m"]),
+                   &b"\x1B[1;31;48;7;31m"[..]);
+    }
 }
@@ -0,0 +1,280 @@
+//! Color spaces, and color math on `Rgb`.
+
+use super::Rgb;
+
+/// A color in the HSL (hue, saturation, lightness) space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsl {
+    /// The hue, in degrees from 0 to 360.
+    pub hue: f64,
+    /// The saturation, from 0 to 1.
+    pub saturation: f64,
+    /// The lightness, from 0 (black) to 1 (white).
+    pub lightness: f64,
+}
+
+impl Hsl {
+    /// The closest `Rgb` color.
+    pub fn to_rgb(self) -> Rgb {
+        let s = clamp(self.saturation);
+        let l = clamp(self.lightness);
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        from_hue(self.hue, chroma, l - chroma / 2.0)
+    }
+}
+
+/// A color in the HSV (hue, saturation, value) space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsv {
+    /// The hue, in degrees from 0 to 360.
+    pub hue: f64,
+    /// The saturation, from 0 to 1.
+    pub saturation: f64,
+    /// The value, from 0 (black) to 1.
+    pub value: f64,
+}
+
+impl Hsv {
+    /// The closest `Rgb` color.
+    pub fn to_rgb(self) -> Rgb {
+        let v = clamp(self.value);
+        let chroma = v * clamp(self.saturation);
+        from_hue(self.hue, chroma, v - chroma)
+    }
+}
+
+/// A color in the linear sRGB space, whose components, from 0 to 1, are proportional to light
+/// intensity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearRgb(pub f64, pub f64, pub f64);
+
+impl LinearRgb {
+    /// The closest `Rgb` color.
+    pub fn to_rgb(self) -> Rgb {
+        let encode = |v: f64| {
+            let v = clamp(v);
+            let v = if v <= 0.003_130_8 {
+                v * 12.92
+            } else {
+                1.055 * v.powf(1.0 / 2.4) - 0.055
+            };
+            to_u8(v)
+        };
+        Rgb(encode(self.0), encode(self.1), encode(self.2))
+    }
+}
+
+/// Clamp a component between 0 and 1.
+fn clamp(v: f64) -> f64 {
+    v.clamp(0.0, 1.0)
+}
+
+/// Convert a component from 0 to 1 to 0–255.
+fn to_u8(v: f64) -> u8 {
+    (clamp(v) * 255.0).round() as u8
+}
+
+/// The `Rgb` color of a hue with the given chroma, raised by `min` on each component.
+fn from_hue(hue: f64, chroma: f64, min: f64) -> Rgb {
+    let h = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u8 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    Rgb(to_u8(r + min), to_u8(g + min), to_u8(b + min))
+}
+
+impl Rgb {
+    /// The components, from 0 to 1.
+    fn to_unit(self) -> (f64, f64, f64) {
+        (f64::from(self.0) / 255.0, f64::from(self.1) / 255.0, f64::from(self.2) / 255.0)
+    }
+
+    /// The hue (in degrees), the largest component and the chroma.
+    fn hue(self) -> (f64, f64, f64) {
+        let (r, g, b) = self.to_unit();
+        let max = r.max(g).max(b);
+        let chroma = max - r.min(g).min(b);
+
+        let hue = if chroma == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / chroma).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / chroma + 2.0)
+        } else {
+            60.0 * ((r - g) / chroma + 4.0)
+        };
+        (hue, max, chroma)
+    }
+
+    /// The color in the HSL space.
+    pub fn to_hsl(self) -> Hsl {
+        let (hue, max, chroma) = self.hue();
+        let lightness = max - chroma / 2.0;
+        let saturation = if lightness == 0.0 || lightness == 1.0 {
+            0.0
+        } else {
+            chroma / (1.0 - (2.0 * lightness - 1.0).abs())
+        };
+        Hsl { hue, saturation, lightness }
+    }
+
+    /// The color in the HSV space.
+    pub fn to_hsv(self) -> Hsv {
+        let (hue, max, chroma) = self.hue();
+        let saturation = if max == 0.0 { 0.0 } else { chroma / max };
+        Hsv { hue, saturation, value: max }
+    }
+
+    /// The color in the linear sRGB space.
+    pub fn to_linear(self) -> LinearRgb {
+        let decode = |v: f64| if v <= 0.04045 {
+            v / 12.92
+        } else {
+            ((v + 0.055) / 1.055).powf(2.4)
+        };
+        let (r, g, b) = self.to_unit();
+        LinearRgb(decode(r), decode(g), decode(b))
+    }
+
+    /// Lighten the color, raising its HSL lightness by `amount` (from 0 to 1).
+    pub fn lighten(self, amount: f64) -> Rgb {
+        let mut hsl = self.to_hsl();
+        hsl.lightness = clamp(hsl.lightness + amount);
+        hsl.to_rgb()
+    }
+
+    /// Darken the color, lowering its HSL lightness by `amount` (from 0 to 1).
+    pub fn darken(self, amount: f64) -> Rgb {
+        self.lighten(-amount)
+    }
+
+    /// Draw `over` on this color with the given opacity, from 0 (this color) to 1 (`over`).
+    ///
+    /// The components are mixed as they are, as in CSS.
+    pub fn blend(self, over: Rgb, alpha: f64) -> Rgb {
+        let alpha = clamp(alpha);
+        let mix = |a: u8, b: u8| {
+            (f64::from(a) + (f64::from(b) - f64::from(a)) * alpha).round() as u8
+        };
+        Rgb(mix(self.0, over.0), mix(self.1, over.1), mix(self.2, over.2))
+    }
+
+    /// The relative luminance of the color, from 0 (black) to 1 (white), as defined by WCAG.
+    pub fn luminance(self) -> f64 {
+        let LinearRgb(r, g, b) = self.to_linear();
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
+    /// The contrast ratio between two colors, from 1 (none) to 21 (black on white), as defined
+    /// by WCAG.
+    ///
+    /// WCAG asks for at least 4.5 for normal text, and 3 for large text.
+    pub fn contrast_ratio(self, other: Rgb) -> f64 {
+        let (a, b) = (self.luminance(), other.luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// The color of `candidates` most readable on this background color, that is with the
+    /// highest contrast ratio, or `None` if there are no candidates.
+    pub fn best_foreground(self, candidates: &[Rgb]) -> Option<Rgb> {
+        candidates.iter().cloned().max_by(|a, b| {
+            self.contrast_ratio(*a).total_cmp(&self.contrast_ratio(*b))
+        })
+    }
+
+    /// Black or white, whichever is the most readable on this background color.
+    pub fn readable_foreground(self) -> Rgb {
+        self.best_foreground(&[Rgb(0, 0, 0), Rgb(255, 255, 255)]).unwrap_or(Rgb(0, 0, 0))
+    }
+}
+
+/// A gradient of `len` colors, going through the `stops`, which are evenly spaced.
+///
+/// The first and last colors are the first and last stops. There are no colors without stops.
+///
+/// # Example
+///
+/// ```rust
+/// use termion::color::{self, Rgb};
+///
+/// for color in color::gradient(&[Rgb(255, 0, 0), Rgb(0, 0, 255)], 40) {
+///     print!("{} ", color::Bg(color));
+/// }
+/// println!("{}", color::Bg(color::Reset));
+/// ```
+pub fn gradient(stops: &[Rgb], len: usize) -> Vec<Rgb> {
+    match (stops, len) {
+        ([], _) | (_, 0) => Vec::new(),
+        (&[stop], _) | (&[stop, ..], 1) => vec![stop; len],
+        _ => {
+            let segments = (stops.len() - 1) as f64;
+            (0..len)
+                .map(|i| {
+                    let pos = i as f64 / (len - 1) as f64 * segments;
+                    let segment = (pos as usize).min(stops.len() - 2);
+                    stops[segment].blend(stops[segment + 1], pos - segment as f64)
+                })
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_spaces() {
+        let colors = [Rgb(0, 0, 0), Rgb(255, 255, 255), Rgb(255, 0, 0), Rgb(18, 52, 86),
+                      Rgb(250, 128, 114), Rgb(127, 127, 127), Rgb(0, 128, 128)];
+        for &color in &colors {
+            assert_eq!(color.to_hsl().to_rgb(), color);
+            assert_eq!(color.to_hsv().to_rgb(), color);
+            assert_eq!(color.to_linear().to_rgb(), color);
+        }
+
+        let hsl = Rgb(255, 128, 0).to_hsl();
+        assert!((hsl.hue - 30.1).abs() < 0.1);
+        assert_eq!(hsl.saturation, 1.0);
+        assert!((hsl.lightness - 0.5).abs() < 0.01);
+        assert_eq!(Hsv { hue: 240.0, saturation: 1.0, value: 1.0 }.to_rgb(), Rgb(0, 0, 255));
+        assert_eq!(Hsl { hue: -240.0, saturation: 1.0, lightness: 0.25 }.to_rgb(), Rgb(0, 128, 0));
+        assert_eq!(LinearRgb(0.5, 0.0, 2.0).to_rgb(), Rgb(188, 0, 255));
+    }
+
+    #[test]
+    fn test_math() {
+        assert_eq!(Rgb(255, 0, 0).lighten(0.25), Rgb(255, 128, 128));
+        assert_eq!(Rgb(255, 0, 0).darken(0.25), Rgb(128, 0, 0));
+        assert_eq!(Rgb(10, 10, 10).darken(1.0), Rgb(0, 0, 0));
+        assert_eq!(Rgb(0, 0, 0).blend(Rgb(255, 100, 10), 0.5), Rgb(128, 50, 5));
+        assert_eq!(Rgb(0, 0, 0).blend(Rgb(255, 100, 10), 2.0), Rgb(255, 100, 10));
+
+        assert_eq!(gradient(&[Rgb(0, 0, 0), Rgb(255, 0, 0), Rgb(255, 255, 255)], 5),
+                   [Rgb(0, 0, 0), Rgb(128, 0, 0), Rgb(255, 0, 0), Rgb(255, 128, 128),
+                    Rgb(255, 255, 255)]);
+        assert_eq!(gradient(&[Rgb(1, 2, 3), Rgb(4, 5, 6)], 1), [Rgb(1, 2, 3)]);
+        assert_eq!(gradient(&[Rgb(1, 2, 3)], 2), [Rgb(1, 2, 3); 2]);
+        assert!(gradient(&[], 2).is_empty());
+    }
+
+    #[test]
+    fn test_contrast() {
+        assert!((Rgb(0, 0, 0).contrast_ratio(Rgb(255, 255, 255)) - 21.0).abs() < 1e-9);
+        assert_eq!(Rgb(18, 52, 86).contrast_ratio(Rgb(18, 52, 86)), 1.0);
+        assert!((Rgb(255, 255, 255).contrast_ratio(Rgb(118, 118, 118)) - 4.54).abs() < 0.01);
+
+        assert_eq!(Rgb(255, 255, 0).readable_foreground(), Rgb(0, 0, 0));
+        assert_eq!(Rgb(0, 0, 128).readable_foreground(), Rgb(255, 255, 255));
+        assert_eq!(Rgb(40, 44, 52).best_foreground(&[Rgb(97, 175, 239), Rgb(92, 99, 112)]),
+                   Some(Rgb(97, 175, 239)));
+        assert_eq!(Rgb(0, 0, 0).best_foreground(&[]), None);
+    }
+}
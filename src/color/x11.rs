@@ -0,0 +1,667 @@
+//! The X11 color names, from `rgb.txt`.
+//!
+//! The names are lowercase, without spaces, and sorted.
+
+use super::Rgb;
+
+/// The X11 colors, by name.
+pub static COLORS: &[(&str, Rgb)] = &[
+    ("aliceblue", Rgb(240, 248, 255)),
+    ("antiquewhite", Rgb(250, 235, 215)),
+    ("antiquewhite1", Rgb(255, 239, 219)),
+    ("antiquewhite2", Rgb(238, 223, 204)),
+    ("antiquewhite3", Rgb(205, 192, 176)),
+    ("antiquewhite4", Rgb(139, 131, 120)),
+    ("aquamarine", Rgb(127, 255, 212)),
+    ("aquamarine1", Rgb(127, 255, 212)),
+    ("aquamarine2", Rgb(118, 238, 198)),
+    ("aquamarine3", Rgb(102, 205, 170)),
+    ("aquamarine4", Rgb(69, 139, 116)),
+    ("azure", Rgb(240, 255, 255)),
+    ("azure1", Rgb(240, 255, 255)),
+    ("azure2", Rgb(224, 238, 238)),
+    ("azure3", Rgb(193, 205, 205)),
+    ("azure4", Rgb(131, 139, 139)),
+    ("beige", Rgb(245, 245, 220)),
+    ("bisque", Rgb(255, 228, 196)),
+    ("bisque1", Rgb(255, 228, 196)),
+    ("bisque2", Rgb(238, 213, 183)),
+    ("bisque3", Rgb(205, 183, 158)),
+    ("bisque4", Rgb(139, 125, 107)),
+    ("black", Rgb(0, 0, 0)),
+    ("blanchedalmond", Rgb(255, 235, 205)),
+    ("blue", Rgb(0, 0, 255)),
+    ("blue1", Rgb(0, 0, 255)),
+    ("blue2", Rgb(0, 0, 238)),
+    ("blue3", Rgb(0, 0, 205)),
+    ("blue4", Rgb(0, 0, 139)),
+    ("blueviolet", Rgb(138, 43, 226)),
+    ("brown", Rgb(165, 42, 42)),
+    ("brown1", Rgb(255, 64, 64)),
+    ("brown2", Rgb(238, 59, 59)),
+    ("brown3", Rgb(205, 51, 51)),
+    ("brown4", Rgb(139, 35, 35)),
+    ("burlywood", Rgb(222, 184, 135)),
+    ("burlywood1", Rgb(255, 211, 155)),
+    ("burlywood2", Rgb(238, 197, 145)),
+    ("burlywood3", Rgb(205, 170, 125)),
+    ("burlywood4", Rgb(139, 115, 85)),
+    ("cadetblue", Rgb(95, 158, 160)),
+    ("cadetblue1", Rgb(152, 245, 255)),
+    ("cadetblue2", Rgb(142, 229, 238)),
+    ("cadetblue3", Rgb(122, 197, 205)),
+    ("cadetblue4", Rgb(83, 134, 139)),
+    ("chartreuse", Rgb(127, 255, 0)),
+    ("chartreuse1", Rgb(127, 255, 0)),
+    ("chartreuse2", Rgb(118, 238, 0)),
+    ("chartreuse3", Rgb(102, 205, 0)),
+    ("chartreuse4", Rgb(69, 139, 0)),
+    ("chocolate", Rgb(210, 105, 30)),
+    ("chocolate1", Rgb(255, 127, 36)),
+    ("chocolate2", Rgb(238, 118, 33)),
+    ("chocolate3", Rgb(205, 102, 29)),
+    ("chocolate4", Rgb(139, 69, 19)),
+    ("coral", Rgb(255, 127, 80)),
+    ("coral1", Rgb(255, 114, 86)),
+    ("coral2", Rgb(238, 106, 80)),
+    ("coral3", Rgb(205, 91, 69)),
+    ("coral4", Rgb(139, 62, 47)),
+    ("cornflowerblue", Rgb(100, 149, 237)),
+    ("cornsilk", Rgb(255, 248, 220)),
+    ("cornsilk1", Rgb(255, 248, 220)),
+    ("cornsilk2", Rgb(238, 232, 205)),
+    ("cornsilk3", Rgb(205, 200, 177)),
+    ("cornsilk4", Rgb(139, 136, 120)),
+    ("cyan", Rgb(0, 255, 255)),
+    ("cyan1", Rgb(0, 255, 255)),
+    ("cyan2", Rgb(0, 238, 238)),
+    ("cyan3", Rgb(0, 205, 205)),
+    ("cyan4", Rgb(0, 139, 139)),
+    ("darkblue", Rgb(0, 0, 139)),
+    ("darkcyan", Rgb(0, 139, 139)),
+    ("darkgoldenrod", Rgb(184, 134, 11)),
+    ("darkgoldenrod1", Rgb(255, 185, 15)),
+    ("darkgoldenrod2", Rgb(238, 173, 14)),
+    ("darkgoldenrod3", Rgb(205, 149, 12)),
+    ("darkgoldenrod4", Rgb(139, 101, 8)),
+    ("darkgray", Rgb(169, 169, 169)),
+    ("darkgreen", Rgb(0, 100, 0)),
+    ("darkgrey", Rgb(169, 169, 169)),
+    ("darkkhaki", Rgb(189, 183, 107)),
+    ("darkmagenta", Rgb(139, 0, 139)),
+    ("darkolivegreen", Rgb(85, 107, 47)),
+    ("darkolivegreen1", Rgb(202, 255, 112)),
+    ("darkolivegreen2", Rgb(188, 238, 104)),
+    ("darkolivegreen3", Rgb(162, 205, 90)),
+    ("darkolivegreen4", Rgb(110, 139, 61)),
+    ("darkorange", Rgb(255, 140, 0)),
+    ("darkorange1", Rgb(255, 127, 0)),
+    ("darkorange2", Rgb(238, 118, 0)),
+    ("darkorange3", Rgb(205, 102, 0)),
+    ("darkorange4", Rgb(139, 69, 0)),
+    ("darkorchid", Rgb(153, 50, 204)),
+    ("darkorchid1", Rgb(191, 62, 255)),
+    ("darkorchid2", Rgb(178, 58, 238)),
+    ("darkorchid3", Rgb(154, 50, 205)),
+    ("darkorchid4", Rgb(104, 34, 139)),
+    ("darkred", Rgb(139, 0, 0)),
+    ("darksalmon", Rgb(233, 150, 122)),
+    ("darkseagreen", Rgb(143, 188, 143)),
+    ("darkseagreen1", Rgb(193, 255, 193)),
+    ("darkseagreen2", Rgb(180, 238, 180)),
+    ("darkseagreen3", Rgb(155, 205, 155)),
+    ("darkseagreen4", Rgb(105, 139, 105)),
+    ("darkslateblue", Rgb(72, 61, 139)),
+    ("darkslategray", Rgb(47, 79, 79)),
+    ("darkslategray1", Rgb(151, 255, 255)),
+    ("darkslategray2", Rgb(141, 238, 238)),
+    ("darkslategray3", Rgb(121, 205, 205)),
+    ("darkslategray4", Rgb(82, 139, 139)),
+    ("darkslategrey", Rgb(47, 79, 79)),
+    ("darkturquoise", Rgb(0, 206, 209)),
+    ("darkviolet", Rgb(148, 0, 211)),
+    ("debianred", Rgb(215, 7, 81)),
+    ("deeppink", Rgb(255, 20, 147)),
+    ("deeppink1", Rgb(255, 20, 147)),
+    ("deeppink2", Rgb(238, 18, 137)),
+    ("deeppink3", Rgb(205, 16, 118)),
+    ("deeppink4", Rgb(139, 10, 80)),
+    ("deepskyblue", Rgb(0, 191, 255)),
+    ("deepskyblue1", Rgb(0, 191, 255)),
+    ("deepskyblue2", Rgb(0, 178, 238)),
+    ("deepskyblue3", Rgb(0, 154, 205)),
+    ("deepskyblue4", Rgb(0, 104, 139)),
+    ("dimgray", Rgb(105, 105, 105)),
+    ("dimgrey", Rgb(105, 105, 105)),
+    ("dodgerblue", Rgb(30, 144, 255)),
+    ("dodgerblue1", Rgb(30, 144, 255)),
+    ("dodgerblue2", Rgb(28, 134, 238)),
+    ("dodgerblue3", Rgb(24, 116, 205)),
+    ("dodgerblue4", Rgb(16, 78, 139)),
+    ("firebrick", Rgb(178, 34, 34)),
+    ("firebrick1", Rgb(255, 48, 48)),
+    ("firebrick2", Rgb(238, 44, 44)),
+    ("firebrick3", Rgb(205, 38, 38)),
+    ("firebrick4", Rgb(139, 26, 26)),
+    ("floralwhite", Rgb(255, 250, 240)),
+    ("forestgreen", Rgb(34, 139, 34)),
+    ("gainsboro", Rgb(220, 220, 220)),
+    ("ghostwhite", Rgb(248, 248, 255)),
+    ("gold", Rgb(255, 215, 0)),
+    ("gold1", Rgb(255, 215, 0)),
+    ("gold2", Rgb(238, 201, 0)),
+    ("gold3", Rgb(205, 173, 0)),
+    ("gold4", Rgb(139, 117, 0)),
+    ("goldenrod", Rgb(218, 165, 32)),
+    ("goldenrod1", Rgb(255, 193, 37)),
+    ("goldenrod2", Rgb(238, 180, 34)),
+    ("goldenrod3", Rgb(205, 155, 29)),
+    ("goldenrod4", Rgb(139, 105, 20)),
+    ("gray", Rgb(190, 190, 190)),
+    ("gray0", Rgb(0, 0, 0)),
+    ("gray1", Rgb(3, 3, 3)),
+    ("gray10", Rgb(26, 26, 26)),
+    ("gray100", Rgb(255, 255, 255)),
+    ("gray11", Rgb(28, 28, 28)),
+    ("gray12", Rgb(31, 31, 31)),
+    ("gray13", Rgb(33, 33, 33)),
+    ("gray14", Rgb(36, 36, 36)),
+    ("gray15", Rgb(38, 38, 38)),
+    ("gray16", Rgb(41, 41, 41)),
+    ("gray17", Rgb(43, 43, 43)),
+    ("gray18", Rgb(46, 46, 46)),
+    ("gray19", Rgb(48, 48, 48)),
+    ("gray2", Rgb(5, 5, 5)),
+    ("gray20", Rgb(51, 51, 51)),
+    ("gray21", Rgb(54, 54, 54)),
+    ("gray22", Rgb(56, 56, 56)),
+    ("gray23", Rgb(59, 59, 59)),
+    ("gray24", Rgb(61, 61, 61)),
+    ("gray25", Rgb(64, 64, 64)),
+    ("gray26", Rgb(66, 66, 66)),
+    ("gray27", Rgb(69, 69, 69)),
+    ("gray28", Rgb(71, 71, 71)),
+    ("gray29", Rgb(74, 74, 74)),
+    ("gray3", Rgb(8, 8, 8)),
+    ("gray30", Rgb(77, 77, 77)),
+    ("gray31", Rgb(79, 79, 79)),
+    ("gray32", Rgb(82, 82, 82)),
+    ("gray33", Rgb(84, 84, 84)),
+    ("gray34", Rgb(87, 87, 87)),
+    ("gray35", Rgb(89, 89, 89)),
+    ("gray36", Rgb(92, 92, 92)),
+    ("gray37", Rgb(94, 94, 94)),
+    ("gray38", Rgb(97, 97, 97)),
+    ("gray39", Rgb(99, 99, 99)),
+    ("gray4", Rgb(10, 10, 10)),
+    ("gray40", Rgb(102, 102, 102)),
+    ("gray41", Rgb(105, 105, 105)),
+    ("gray42", Rgb(107, 107, 107)),
+    ("gray43", Rgb(110, 110, 110)),
+    ("gray44", Rgb(112, 112, 112)),
+    ("gray45", Rgb(115, 115, 115)),
+    ("gray46", Rgb(117, 117, 117)),
+    ("gray47", Rgb(120, 120, 120)),
+    ("gray48", Rgb(122, 122, 122)),
+    ("gray49", Rgb(125, 125, 125)),
+    ("gray5", Rgb(13, 13, 13)),
+    ("gray50", Rgb(127, 127, 127)),
+    ("gray51", Rgb(130, 130, 130)),
+    ("gray52", Rgb(133, 133, 133)),
+    ("gray53", Rgb(135, 135, 135)),
+    ("gray54", Rgb(138, 138, 138)),
+    ("gray55", Rgb(140, 140, 140)),
+    ("gray56", Rgb(143, 143, 143)),
+    ("gray57", Rgb(145, 145, 145)),
+    ("gray58", Rgb(148, 148, 148)),
+    ("gray59", Rgb(150, 150, 150)),
+    ("gray6", Rgb(15, 15, 15)),
+    ("gray60", Rgb(153, 153, 153)),
+    ("gray61", Rgb(156, 156, 156)),
+    ("gray62", Rgb(158, 158, 158)),
+    ("gray63", Rgb(161, 161, 161)),
+    ("gray64", Rgb(163, 163, 163)),
+    ("gray65", Rgb(166, 166, 166)),
+    ("gray66", Rgb(168, 168, 168)),
+    ("gray67", Rgb(171, 171, 171)),
+    ("gray68", Rgb(173, 173, 173)),
+    ("gray69", Rgb(176, 176, 176)),
+    ("gray7", Rgb(18, 18, 18)),
+    ("gray70", Rgb(179, 179, 179)),
+    ("gray71", Rgb(181, 181, 181)),
+    ("gray72", Rgb(184, 184, 184)),
+    ("gray73", Rgb(186, 186, 186)),
+    ("gray74", Rgb(189, 189, 189)),
+    ("gray75", Rgb(191, 191, 191)),
+    ("gray76", Rgb(194, 194, 194)),
+    ("gray77", Rgb(196, 196, 196)),
+    ("gray78", Rgb(199, 199, 199)),
+    ("gray79", Rgb(201, 201, 201)),
+    ("gray8", Rgb(20, 20, 20)),
+    ("gray80", Rgb(204, 204, 204)),
+    ("gray81", Rgb(207, 207, 207)),
+    ("gray82", Rgb(209, 209, 209)),
+    ("gray83", Rgb(212, 212, 212)),
+    ("gray84", Rgb(214, 214, 214)),
+    ("gray85", Rgb(217, 217, 217)),
+    ("gray86", Rgb(219, 219, 219)),
+    ("gray87", Rgb(222, 222, 222)),
+    ("gray88", Rgb(224, 224, 224)),
+    ("gray89", Rgb(227, 227, 227)),
+    ("gray9", Rgb(23, 23, 23)),
+    ("gray90", Rgb(229, 229, 229)),
+    ("gray91", Rgb(232, 232, 232)),
+    ("gray92", Rgb(235, 235, 235)),
+    ("gray93", Rgb(237, 237, 237)),
+    ("gray94", Rgb(240, 240, 240)),
+    ("gray95", Rgb(242, 242, 242)),
+    ("gray96", Rgb(245, 245, 245)),
+    ("gray97", Rgb(247, 247, 247)),
+    ("gray98", Rgb(250, 250, 250)),
+    ("gray99", Rgb(252, 252, 252)),
+    ("green", Rgb(0, 255, 0)),
+    ("green1", Rgb(0, 255, 0)),
+    ("green2", Rgb(0, 238, 0)),
+    ("green3", Rgb(0, 205, 0)),
+    ("green4", Rgb(0, 139, 0)),
+    ("greenyellow", Rgb(173, 255, 47)),
+    ("grey", Rgb(190, 190, 190)),
+    ("grey0", Rgb(0, 0, 0)),
+    ("grey1", Rgb(3, 3, 3)),
+    ("grey10", Rgb(26, 26, 26)),
+    ("grey100", Rgb(255, 255, 255)),
+    ("grey11", Rgb(28, 28, 28)),
+    ("grey12", Rgb(31, 31, 31)),
+    ("grey13", Rgb(33, 33, 33)),
+    ("grey14", Rgb(36, 36, 36)),
+    ("grey15", Rgb(38, 38, 38)),
+    ("grey16", Rgb(41, 41, 41)),
+    ("grey17", Rgb(43, 43, 43)),
+    ("grey18", Rgb(46, 46, 46)),
+    ("grey19", Rgb(48, 48, 48)),
+    ("grey2", Rgb(5, 5, 5)),
+    ("grey20", Rgb(51, 51, 51)),
+    ("grey21", Rgb(54, 54, 54)),
+    ("grey22", Rgb(56, 56, 56)),
+    ("grey23", Rgb(59, 59, 59)),
+    ("grey24", Rgb(61, 61, 61)),
+    ("grey25", Rgb(64, 64, 64)),
+    ("grey26", Rgb(66, 66, 66)),
+    ("grey27", Rgb(69, 69, 69)),
+    ("grey28", Rgb(71, 71, 71)),
+    ("grey29", Rgb(74, 74, 74)),
+    ("grey3", Rgb(8, 8, 8)),
+    ("grey30", Rgb(77, 77, 77)),
+    ("grey31", Rgb(79, 79, 79)),
+    ("grey32", Rgb(82, 82, 82)),
+    ("grey33", Rgb(84, 84, 84)),
+    ("grey34", Rgb(87, 87, 87)),
+    ("grey35", Rgb(89, 89, 89)),
+    ("grey36", Rgb(92, 92, 92)),
+    ("grey37", Rgb(94, 94, 94)),
+    ("grey38", Rgb(97, 97, 97)),
+    ("grey39", Rgb(99, 99, 99)),
+    ("grey4", Rgb(10, 10, 10)),
+    ("grey40", Rgb(102, 102, 102)),
+    ("grey41", Rgb(105, 105, 105)),
+    ("grey42", Rgb(107, 107, 107)),
+    ("grey43", Rgb(110, 110, 110)),
+    ("grey44", Rgb(112, 112, 112)),
+    ("grey45", Rgb(115, 115, 115)),
+    ("grey46", Rgb(117, 117, 117)),
+    ("grey47", Rgb(120, 120, 120)),
+    ("grey48", Rgb(122, 122, 122)),
+    ("grey49", Rgb(125, 125, 125)),
+    ("grey5", Rgb(13, 13, 13)),
+    ("grey50", Rgb(127, 127, 127)),
+    ("grey51", Rgb(130, 130, 130)),
+    ("grey52", Rgb(133, 133, 133)),
+    ("grey53", Rgb(135, 135, 135)),
+    ("grey54", Rgb(138, 138, 138)),
+    ("grey55", Rgb(140, 140, 140)),
+    ("grey56", Rgb(143, 143, 143)),
+    ("grey57", Rgb(145, 145, 145)),
+    ("grey58", Rgb(148, 148, 148)),
+    ("grey59", Rgb(150, 150, 150)),
+    ("grey6", Rgb(15, 15, 15)),
+    ("grey60", Rgb(153, 153, 153)),
+    ("grey61", Rgb(156, 156, 156)),
+    ("grey62", Rgb(158, 158, 158)),
+    ("grey63", Rgb(161, 161, 161)),
+    ("grey64", Rgb(163, 163, 163)),
+    ("grey65", Rgb(166, 166, 166)),
+    ("grey66", Rgb(168, 168, 168)),
+    ("grey67", Rgb(171, 171, 171)),
+    ("grey68", Rgb(173, 173, 173)),
+    ("grey69", Rgb(176, 176, 176)),
+    ("grey7", Rgb(18, 18, 18)),
+    ("grey70", Rgb(179, 179, 179)),
+    ("grey71", Rgb(181, 181, 181)),
+    ("grey72", Rgb(184, 184, 184)),
+    ("grey73", Rgb(186, 186, 186)),
+    ("grey74", Rgb(189, 189, 189)),
+    ("grey75", Rgb(191, 191, 191)),
+    ("grey76", Rgb(194, 194, 194)),
+    ("grey77", Rgb(196, 196, 196)),
+    ("grey78", Rgb(199, 199, 199)),
+    ("grey79", Rgb(201, 201, 201)),
+    ("grey8", Rgb(20, 20, 20)),
+    ("grey80", Rgb(204, 204, 204)),
+    ("grey81", Rgb(207, 207, 207)),
+    ("grey82", Rgb(209, 209, 209)),
+    ("grey83", Rgb(212, 212, 212)),
+    ("grey84", Rgb(214, 214, 214)),
+    ("grey85", Rgb(217, 217, 217)),
+    ("grey86", Rgb(219, 219, 219)),
+    ("grey87", Rgb(222, 222, 222)),
+    ("grey88", Rgb(224, 224, 224)),
+    ("grey89", Rgb(227, 227, 227)),
+    ("grey9", Rgb(23, 23, 23)),
+    ("grey90", Rgb(229, 229, 229)),
+    ("grey91", Rgb(232, 232, 232)),
+    ("grey92", Rgb(235, 235, 235)),
+    ("grey93", Rgb(237, 237, 237)),
+    ("grey94", Rgb(240, 240, 240)),
+    ("grey95", Rgb(242, 242, 242)),
+    ("grey96", Rgb(245, 245, 245)),
+    ("grey97", Rgb(247, 247, 247)),
+    ("grey98", Rgb(250, 250, 250)),
+    ("grey99", Rgb(252, 252, 252)),
+    ("honeydew", Rgb(240, 255, 240)),
+    ("honeydew1", Rgb(240, 255, 240)),
+    ("honeydew2", Rgb(224, 238, 224)),
+    ("honeydew3", Rgb(193, 205, 193)),
+    ("honeydew4", Rgb(131, 139, 131)),
+    ("hotpink", Rgb(255, 105, 180)),
+    ("hotpink1", Rgb(255, 110, 180)),
+    ("hotpink2", Rgb(238, 106, 167)),
+    ("hotpink3", Rgb(205, 96, 144)),
+    ("hotpink4", Rgb(139, 58, 98)),
+    ("indianred", Rgb(205, 92, 92)),
+    ("indianred1", Rgb(255, 106, 106)),
+    ("indianred2", Rgb(238, 99, 99)),
+    ("indianred3", Rgb(205, 85, 85)),
+    ("indianred4", Rgb(139, 58, 58)),
+    ("ivory", Rgb(255, 255, 240)),
+    ("ivory1", Rgb(255, 255, 240)),
+    ("ivory2", Rgb(238, 238, 224)),
+    ("ivory3", Rgb(205, 205, 193)),
+    ("ivory4", Rgb(139, 139, 131)),
+    ("khaki", Rgb(240, 230, 140)),
+    ("khaki1", Rgb(255, 246, 143)),
+    ("khaki2", Rgb(238, 230, 133)),
+    ("khaki3", Rgb(205, 198, 115)),
+    ("khaki4", Rgb(139, 134, 78)),
+    ("lavender", Rgb(230, 230, 250)),
+    ("lavenderblush", Rgb(255, 240, 245)),
+    ("lavenderblush1", Rgb(255, 240, 245)),
+    ("lavenderblush2", Rgb(238, 224, 229)),
+    ("lavenderblush3", Rgb(205, 193, 197)),
+    ("lavenderblush4", Rgb(139, 131, 134)),
+    ("lawngreen", Rgb(124, 252, 0)),
+    ("lemonchiffon", Rgb(255, 250, 205)),
+    ("lemonchiffon1", Rgb(255, 250, 205)),
+    ("lemonchiffon2", Rgb(238, 233, 191)),
+    ("lemonchiffon3", Rgb(205, 201, 165)),
+    ("lemonchiffon4", Rgb(139, 137, 112)),
+    ("lightblue", Rgb(173, 216, 230)),
+    ("lightblue1", Rgb(191, 239, 255)),
+    ("lightblue2", Rgb(178, 223, 238)),
+    ("lightblue3", Rgb(154, 192, 205)),
+    ("lightblue4", Rgb(104, 131, 139)),
+    ("lightcoral", Rgb(240, 128, 128)),
+    ("lightcyan", Rgb(224, 255, 255)),
+    ("lightcyan1", Rgb(224, 255, 255)),
+    ("lightcyan2", Rgb(209, 238, 238)),
+    ("lightcyan3", Rgb(180, 205, 205)),
+    ("lightcyan4", Rgb(122, 139, 139)),
+    ("lightgoldenrod", Rgb(238, 221, 130)),
+    ("lightgoldenrod1", Rgb(255, 236, 139)),
+    ("lightgoldenrod2", Rgb(238, 220, 130)),
+    ("lightgoldenrod3", Rgb(205, 190, 112)),
+    ("lightgoldenrod4", Rgb(139, 129, 76)),
+    ("lightgoldenrodyellow", Rgb(250, 250, 210)),
+    ("lightgray", Rgb(211, 211, 211)),
+    ("lightgreen", Rgb(144, 238, 144)),
+    ("lightgrey", Rgb(211, 211, 211)),
+    ("lightpink", Rgb(255, 182, 193)),
+    ("lightpink1", Rgb(255, 174, 185)),
+    ("lightpink2", Rgb(238, 162, 173)),
+    ("lightpink3", Rgb(205, 140, 149)),
+    ("lightpink4", Rgb(139, 95, 101)),
+    ("lightsalmon", Rgb(255, 160, 122)),
+    ("lightsalmon1", Rgb(255, 160, 122)),
+    ("lightsalmon2", Rgb(238, 149, 114)),
+    ("lightsalmon3", Rgb(205, 129, 98)),
+    ("lightsalmon4", Rgb(139, 87, 66)),
+    ("lightseagreen", Rgb(32, 178, 170)),
+    ("lightskyblue", Rgb(135, 206, 250)),
+    ("lightskyblue1", Rgb(176, 226, 255)),
+    ("lightskyblue2", Rgb(164, 211, 238)),
+    ("lightskyblue3", Rgb(141, 182, 205)),
+    ("lightskyblue4", Rgb(96, 123, 139)),
+    ("lightslateblue", Rgb(132, 112, 255)),
+    ("lightslategray", Rgb(119, 136, 153)),
+    ("lightslategrey", Rgb(119, 136, 153)),
+    ("lightsteelblue", Rgb(176, 196, 222)),
+    ("lightsteelblue1", Rgb(202, 225, 255)),
+    ("lightsteelblue2", Rgb(188, 210, 238)),
+    ("lightsteelblue3", Rgb(162, 181, 205)),
+    ("lightsteelblue4", Rgb(110, 123, 139)),
+    ("lightyellow", Rgb(255, 255, 224)),
+    ("lightyellow1", Rgb(255, 255, 224)),
+    ("lightyellow2", Rgb(238, 238, 209)),
+    ("lightyellow3", Rgb(205, 205, 180)),
+    ("lightyellow4", Rgb(139, 139, 122)),
+    ("limegreen", Rgb(50, 205, 50)),
+    ("linen", Rgb(250, 240, 230)),
+    ("magenta", Rgb(255, 0, 255)),
+    ("magenta1", Rgb(255, 0, 255)),
+    ("magenta2", Rgb(238, 0, 238)),
+    ("magenta3", Rgb(205, 0, 205)),
+    ("magenta4", Rgb(139, 0, 139)),
+    ("maroon", Rgb(176, 48, 96)),
+    ("maroon1", Rgb(255, 52, 179)),
+    ("maroon2", Rgb(238, 48, 167)),
+    ("maroon3", Rgb(205, 41, 144)),
+    ("maroon4", Rgb(139, 28, 98)),
+    ("mediumaquamarine", Rgb(102, 205, 170)),
+    ("mediumblue", Rgb(0, 0, 205)),
+    ("mediumorchid", Rgb(186, 85, 211)),
+    ("mediumorchid1", Rgb(224, 102, 255)),
+    ("mediumorchid2", Rgb(209, 95, 238)),
+    ("mediumorchid3", Rgb(180, 82, 205)),
+    ("mediumorchid4", Rgb(122, 55, 139)),
+    ("mediumpurple", Rgb(147, 112, 219)),
+    ("mediumpurple1", Rgb(171, 130, 255)),
+    ("mediumpurple2", Rgb(159, 121, 238)),
+    ("mediumpurple3", Rgb(137, 104, 205)),
+    ("mediumpurple4", Rgb(93, 71, 139)),
+    ("mediumseagreen", Rgb(60, 179, 113)),
+    ("mediumslateblue", Rgb(123, 104, 238)),
+    ("mediumspringgreen", Rgb(0, 250, 154)),
+    ("mediumturquoise", Rgb(72, 209, 204)),
+    ("mediumvioletred", Rgb(199, 21, 133)),
+    ("midnightblue", Rgb(25, 25, 112)),
+    ("mintcream", Rgb(245, 255, 250)),
+    ("mistyrose", Rgb(255, 228, 225)),
+    ("mistyrose1", Rgb(255, 228, 225)),
+    ("mistyrose2", Rgb(238, 213, 210)),
+    ("mistyrose3", Rgb(205, 183, 181)),
+    ("mistyrose4", Rgb(139, 125, 123)),
+    ("moccasin", Rgb(255, 228, 181)),
+    ("navajowhite", Rgb(255, 222, 173)),
+    ("navajowhite1", Rgb(255, 222, 173)),
+    ("navajowhite2", Rgb(238, 207, 161)),
+    ("navajowhite3", Rgb(205, 179, 139)),
+    ("navajowhite4", Rgb(139, 121, 94)),
+    ("navy", Rgb(0, 0, 128)),
+    ("navyblue", Rgb(0, 0, 128)),
+    ("oldlace", Rgb(253, 245, 230)),
+    ("olivedrab", Rgb(107, 142, 35)),
+    ("olivedrab1", Rgb(192, 255, 62)),
+    ("olivedrab2", Rgb(179, 238, 58)),
+    ("olivedrab3", Rgb(154, 205, 50)),
+    ("olivedrab4", Rgb(105, 139, 34)),
+    ("orange", Rgb(255, 165, 0)),
+    ("orange1", Rgb(255, 165, 0)),
+    ("orange2", Rgb(238, 154, 0)),
+    ("orange3", Rgb(205, 133, 0)),
+    ("orange4", Rgb(139, 90, 0)),
+    ("orangered", Rgb(255, 69, 0)),
+    ("orangered1", Rgb(255, 69, 0)),
+    ("orangered2", Rgb(238, 64, 0)),
+    ("orangered3", Rgb(205, 55, 0)),
+    ("orangered4", Rgb(139, 37, 0)),
+    ("orchid", Rgb(218, 112, 214)),
+    ("orchid1", Rgb(255, 131, 250)),
+    ("orchid2", Rgb(238, 122, 233)),
+    ("orchid3", Rgb(205, 105, 201)),
+    ("orchid4", Rgb(139, 71, 137)),
+    ("palegoldenrod", Rgb(238, 232, 170)),
+    ("palegreen", Rgb(152, 251, 152)),
+    ("palegreen1", Rgb(154, 255, 154)),
+    ("palegreen2", Rgb(144, 238, 144)),
+    ("palegreen3", Rgb(124, 205, 124)),
+    ("palegreen4", Rgb(84, 139, 84)),
+    ("paleturquoise", Rgb(175, 238, 238)),
+    ("paleturquoise1", Rgb(187, 255, 255)),
+    ("paleturquoise2", Rgb(174, 238, 238)),
+    ("paleturquoise3", Rgb(150, 205, 205)),
+    ("paleturquoise4", Rgb(102, 139, 139)),
+    ("palevioletred", Rgb(219, 112, 147)),
+    ("palevioletred1", Rgb(255, 130, 171)),
+    ("palevioletred2", Rgb(238, 121, 159)),
+    ("palevioletred3", Rgb(205, 104, 137)),
+    ("palevioletred4", Rgb(139, 71, 93)),
+    ("papayawhip", Rgb(255, 239, 213)),
+    ("peachpuff", Rgb(255, 218, 185)),
+    ("peachpuff1", Rgb(255, 218, 185)),
+    ("peachpuff2", Rgb(238, 203, 173)),
+    ("peachpuff3", Rgb(205, 175, 149)),
+    ("peachpuff4", Rgb(139, 119, 101)),
+    ("peru", Rgb(205, 133, 63)),
+    ("pink", Rgb(255, 192, 203)),
+    ("pink1", Rgb(255, 181, 197)),
+    ("pink2", Rgb(238, 169, 184)),
+    ("pink3", Rgb(205, 145, 158)),
+    ("pink4", Rgb(139, 99, 108)),
+    ("plum", Rgb(221, 160, 221)),
+    ("plum1", Rgb(255, 187, 255)),
+    ("plum2", Rgb(238, 174, 238)),
+    ("plum3", Rgb(205, 150, 205)),
+    ("plum4", Rgb(139, 102, 139)),
+    ("powderblue", Rgb(176, 224, 230)),
+    ("purple", Rgb(160, 32, 240)),
+    ("purple1", Rgb(155, 48, 255)),
+    ("purple2", Rgb(145, 44, 238)),
+    ("purple3", Rgb(125, 38, 205)),
+    ("purple4", Rgb(85, 26, 139)),
+    ("red", Rgb(255, 0, 0)),
+    ("red1", Rgb(255, 0, 0)),
+    ("red2", Rgb(238, 0, 0)),
+    ("red3", Rgb(205, 0, 0)),
+    ("red4", Rgb(139, 0, 0)),
+    ("rosybrown", Rgb(188, 143, 143)),
+    ("rosybrown1", Rgb(255, 193, 193)),
+    ("rosybrown2", Rgb(238, 180, 180)),
+    ("rosybrown3", Rgb(205, 155, 155)),
+    ("rosybrown4", Rgb(139, 105, 105)),
+    ("royalblue", Rgb(65, 105, 225)),
+    ("royalblue1", Rgb(72, 118, 255)),
+    ("royalblue2", Rgb(67, 110, 238)),
+    ("royalblue3", Rgb(58, 95, 205)),
+    ("royalblue4", Rgb(39, 64, 139)),
+    ("saddlebrown", Rgb(139, 69, 19)),
+    ("salmon", Rgb(250, 128, 114)),
+    ("salmon1", Rgb(255, 140, 105)),
+    ("salmon2", Rgb(238, 130, 98)),
+    ("salmon3", Rgb(205, 112, 84)),
+    ("salmon4", Rgb(139, 76, 57)),
+    ("sandybrown", Rgb(244, 164, 96)),
+    ("seagreen", Rgb(46, 139, 87)),
+    ("seagreen1", Rgb(84, 255, 159)),
+    ("seagreen2", Rgb(78, 238, 148)),
+    ("seagreen3", Rgb(67, 205, 128)),
+    ("seagreen4", Rgb(46, 139, 87)),
+    ("seashell", Rgb(255, 245, 238)),
+    ("seashell1", Rgb(255, 245, 238)),
+    ("seashell2", Rgb(238, 229, 222)),
+    ("seashell3", Rgb(205, 197, 191)),
+    ("seashell4", Rgb(139, 134, 130)),
+    ("sienna", Rgb(160, 82, 45)),
+    ("sienna1", Rgb(255, 130, 71)),
+    ("sienna2", Rgb(238, 121, 66)),
+    ("sienna3", Rgb(205, 104, 57)),
+    ("sienna4", Rgb(139, 71, 38)),
+    ("skyblue", Rgb(135, 206, 235)),
+    ("skyblue1", Rgb(135, 206, 255)),
+    ("skyblue2", Rgb(126, 192, 238)),
+    ("skyblue3", Rgb(108, 166, 205)),
+    ("skyblue4", Rgb(74, 112, 139)),
+    ("slateblue", Rgb(106, 90, 205)),
+    ("slateblue1", Rgb(131, 111, 255)),
+    ("slateblue2", Rgb(122, 103, 238)),
+    ("slateblue3", Rgb(105, 89, 205)),
+    ("slateblue4", Rgb(71, 60, 139)),
+    ("slategray", Rgb(112, 128, 144)),
+    ("slategray1", Rgb(198, 226, 255)),
+    ("slategray2", Rgb(185, 211, 238)),
+    ("slategray3", Rgb(159, 182, 205)),
+    ("slategray4", Rgb(108, 123, 139)),
+    ("slategrey", Rgb(112, 128, 144)),
+    ("snow", Rgb(255, 250, 250)),
+    ("snow1", Rgb(255, 250, 250)),
+    ("snow2", Rgb(238, 233, 233)),
+    ("snow3", Rgb(205, 201, 201)),
+    ("snow4", Rgb(139, 137, 137)),
+    ("springgreen", Rgb(0, 255, 127)),
+    ("springgreen1", Rgb(0, 255, 127)),
+    ("springgreen2", Rgb(0, 238, 118)),
+    ("springgreen3", Rgb(0, 205, 102)),
+    ("springgreen4", Rgb(0, 139, 69)),
+    ("steelblue", Rgb(70, 130, 180)),
+    ("steelblue1", Rgb(99, 184, 255)),
+    ("steelblue2", Rgb(92, 172, 238)),
+    ("steelblue3", Rgb(79, 148, 205)),
+    ("steelblue4", Rgb(54, 100, 139)),
+    ("tan", Rgb(210, 180, 140)),
+    ("tan1", Rgb(255, 165, 79)),
+    ("tan2", Rgb(238, 154, 73)),
+    ("tan3", Rgb(205, 133, 63)),
+    ("tan4", Rgb(139, 90, 43)),
+    ("thistle", Rgb(216, 191, 216)),
+    ("thistle1", Rgb(255, 225, 255)),
+    ("thistle2", Rgb(238, 210, 238)),
+    ("thistle3", Rgb(205, 181, 205)),
+    ("thistle4", Rgb(139, 123, 139)),
+    ("tomato", Rgb(255, 99, 71)),
+    ("tomato1", Rgb(255, 99, 71)),
+    ("tomato2", Rgb(238, 92, 66)),
+    ("tomato3", Rgb(205, 79, 57)),
+    ("tomato4", Rgb(139, 54, 38)),
+    ("turquoise", Rgb(64, 224, 208)),
+    ("turquoise1", Rgb(0, 245, 255)),
+    ("turquoise2", Rgb(0, 229, 238)),
+    ("turquoise3", Rgb(0, 197, 205)),
+    ("turquoise4", Rgb(0, 134, 139)),
+    ("violet", Rgb(238, 130, 238)),
+    ("violetred", Rgb(208, 32, 144)),
+    ("violetred1", Rgb(255, 62, 150)),
+    ("violetred2", Rgb(238, 58, 140)),
+    ("violetred3", Rgb(205, 50, 120)),
+    ("violetred4", Rgb(139, 34, 82)),
+    ("wheat", Rgb(245, 222, 179)),
+    ("wheat1", Rgb(255, 231, 186)),
+    ("wheat2", Rgb(238, 216, 174)),
+    ("wheat3", Rgb(205, 186, 150)),
+    ("wheat4", Rgb(139, 126, 102)),
+    ("white", Rgb(255, 255, 255)),
+    ("whitesmoke", Rgb(245, 245, 245)),
+    ("yellow", Rgb(255, 255, 0)),
+    ("yellow1", Rgb(255, 255, 0)),
+    ("yellow2", Rgb(238, 238, 0)),
+    ("yellow3", Rgb(205, 205, 0)),
+    ("yellow4", Rgb(139, 139, 0)),
+    ("yellowgreen", Rgb(154, 205, 50)),
+];
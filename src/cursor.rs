@@ -1,10 +1,12 @@
 //! Cursor movement.
 
 use std::fmt;
-use std::io::{self, Write, Error, ErrorKind, Read};
+use std::io::{self, Write, Read};
+use std::str;
 use async::async_stdin;
-use std::time::{SystemTime, Duration};
-use raw::CONTROL_SEQUENCE_TIMEOUT;
+use std::time::Duration;
+use error::Error;
+use query::{find_sequence, query, Unread, DEFAULT_TIMEOUT};
 
 derive_csi_sequence!("Hide the cursor.", Hide, "?25l");
 derive_csi_sequence!("Show the cursor.", Show, "?25h");
@@ -87,54 +89,78 @@ impl fmt::Display for Down {
 }
 
 /// Types that allow detection of the cursor position.
+#[deprecated(note = "use `query_cursor_pos`, with the reader of the program")]
 pub trait DetectCursorPos {
     /// Get the (1,1)-based cursor position from the terminal.
+    ///
+    /// The answer is read from a new `async_stdin`, whose thread keeps reading the input
+    /// afterwards, so the next key typed is lost. `query_cursor_pos` reads the answer from the
+    /// reader of the program instead, and puts the keys typed meanwhile back into it.
+    #[deprecated(note = "use `query_cursor_pos`, with the reader of the program")]
     fn cursor_pos(&mut self) -> io::Result<(u16, u16)>;
 }
 
+#[allow(deprecated)]
 impl<W: Write> DetectCursorPos for W {
     fn cursor_pos(&mut self) -> io::Result<(u16, u16)> {
-        let mut stdin = async_stdin();
-
-        // Where is the cursor?
-        // Use `ESC [ 6 n`.
-        write!(self, "\x1B[6n")?;
-        self.flush()?;
+        query_cursor_pos(self, &mut async_stdin(), DEFAULT_TIMEOUT)
+    }
+}
 
-        let mut buf: [u8; 1] = [0];
-        let mut read_chars = Vec::new();
+/// Get the (1,1)-based cursor position from the terminal, reading the answer from `reader`.
+///
+/// The other input read meanwhile is put back into `reader`. See the `query` module.
+pub fn query_cursor_pos<W, R>(writer: &mut W, reader: &mut R, timeout: Duration)
+                              -> io::Result<(u16, u16)>
+    where W: Write + ?Sized,
+          R: Read + Unread + ?Sized
+{
+    // Where is the cursor?
+    // Use `ESC [ 6 n`, answered with `ESC [ Cy ; Cx R`.
+    query(writer, reader, "\x1B[6n", timeout, |input| {
+        let is_param = |b: u8| b.is_ascii_digit() || b == b';';
+        let (range, params) = find_sequence(input, b"\x1B[", is_param, b"R")?;
+        let pos = parse_cursor_pos(&input[params])
+            .ok_or_else(|| Error::MalformedResponse { bytes: input[range.clone()].to_vec() });
+        Some((pos, range))
+    })
+}
 
-        let timeout = Duration::from_millis(CONTROL_SEQUENCE_TIMEOUT);
-        let now = SystemTime::now();
+/// Parse the `Cy ; Cx` parameters of a cursor position report.
+fn parse_cursor_pos(params: &[u8]) -> Option<(u16, u16)> {
+    let params = str::from_utf8(params).ok()?;
+    let mut nums = params.split(';').map(|n| n.parse::<u16>().ok());
 
-        // Either consume all data up to R or wait for a timeout.
-        while buf[0] != b'R' && now.elapsed().unwrap() < timeout {
-            if stdin.read(&mut buf)? > 0 {
-                read_chars.push(buf[0]);
-            }
-        }
+    match (nums.next(), nums.next(), nums.next()) {
+        (Some(Some(cy)), Some(Some(cx)), None) => Some((cx, cy)),
+        _ => None,
+    }
+}
 
-        if read_chars.len() == 0 {
-            return Err(Error::new(ErrorKind::Other, "Cursor position detection timed out."));
+#[cfg(test)]
+mod test {
+    use super::*;
+    use query::Pushback;
+
+    #[test]
+    fn test_query_cursor_pos() {
+        let mut written = Vec::new();
+        let mut reader = Pushback::new(&b"a\x1B[A\x1B[12;40Rb"[..]);
+
+        let pos = query_cursor_pos(&mut written, &mut reader, DEFAULT_TIMEOUT).unwrap();
+        assert_eq!(pos, (40, 12));
+        assert_eq!(written, b"\x1B[6n");
+
+        // The keys typed around the answer are kept.
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, b"a\x1B[Ab");
+
+        let mut reader = Pushback::new(&b"\x1B[12;40;1R"[..]);
+        match Error::from(query_cursor_pos(&mut written, &mut reader, DEFAULT_TIMEOUT)
+                              .unwrap_err()) {
+            Error::MalformedResponse { bytes } => assert_eq!(bytes, b"\x1B[12;40;1R"),
+            err => panic!("unexpected error: {:?}", err),
         }
-
-        // The answer will look like `ESC [ Cy ; Cx R`.
-
-        read_chars.pop(); // remove trailing R.
-        let read_str = String::from_utf8(read_chars).unwrap();
-        let beg = read_str.rfind('[').unwrap();
-        let coords: String = read_str.chars().skip(beg + 1).collect();
-        let mut nums = coords.split(';');
-
-        let cy = nums.next()
-            .unwrap()
-            .parse::<u16>()
-            .unwrap();
-        let cx = nums.next()
-            .unwrap()
-            .parse::<u16>()
-            .unwrap();
-
-        Ok((cx, cy))
     }
 }
@@ -0,0 +1,175 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+/// An error reported by termion.
+///
+/// The functions of termion return `io::Result`, but their errors can be converted back into this
+/// type to match on the kind of failure:
+///
+/// ```rust,no_run
+/// use termion::async_stdin;
+/// use termion::cursor;
+/// use termion::query::DEFAULT_TIMEOUT;
+/// use termion::raw::IntoRawMode;
+/// use std::io::stdout;
+///
+/// let mut stdout = stdout().into_raw_mode().unwrap();
+/// let mut stdin = async_stdin();
+/// match cursor::query_cursor_pos(&mut stdout, &mut stdin, DEFAULT_TIMEOUT)
+///           .map_err(termion::Error::from) {
+///     Ok((x, y)) => println!("The cursor is at ({}, {}).", x, y),
+///     Err(termion::Error::QueryTimeout { .. }) => println!("The terminal did not answer."),
+///     Err(e) => println!("{}", e),
+/// }
+/// ```
+///
+/// More kinds of errors may be added: `match`es on errors need a wildcard arm.
+#[allow(clippy::manual_non_exhaustive)]
+#[derive(Debug)]
+pub enum Error {
+    /// The file descriptor does not refer to a terminal, or there is no controlling terminal.
+    NotATty,
+    /// The terminal did not answer a query in time.
+    QueryTimeout {
+        /// What was asked.
+        query: String,
+    },
+    /// The terminal sent bytes which could not be parsed.
+    MalformedResponse {
+        /// The bytes which were read.
+        bytes: Vec<u8>,
+    },
+    /// The terminal sent text which is not valid UTF-8.
+    InvalidUtf8 {
+        /// The bytes which were read.
+        bytes: Vec<u8>,
+    },
+    /// A terminfo entry could not be parsed.
+    InvalidTerminfo {
+        /// What is wrong with it.
+        reason: String,
+    },
+    /// There is no terminfo entry for the terminal.
+    UnknownTerminal {
+        /// The name of the terminal.
+        name: String,
+    },
+    /// The terminfo entry of the terminal lacks a capability.
+    MissingCapability {
+        /// The name of the capability.
+        name: String,
+    },
+    /// A color could not be parsed.
+    InvalidColor {
+        /// The text of the color.
+        text: String,
+    },
+    /// An I/O error.
+    Io(io::Error),
+
+    #[doc(hidden)]
+    __IsNotComplete,
+}
+
+impl Error {
+    /// The closest `io::ErrorKind` to this error.
+    pub fn kind(&self) -> io::ErrorKind {
+        match *self {
+            Error::NotATty => io::ErrorKind::Other,
+            Error::QueryTimeout { .. } => io::ErrorKind::TimedOut,
+            Error::MalformedResponse { .. } |
+            Error::InvalidUtf8 { .. } |
+            Error::InvalidTerminfo { .. } => io::ErrorKind::InvalidData,
+            Error::UnknownTerminal { .. } => io::ErrorKind::NotFound,
+            Error::MissingCapability { .. } => io::ErrorKind::Unsupported,
+            Error::InvalidColor { .. } => io::ErrorKind::InvalidInput,
+            Error::Io(ref e) => e.kind(),
+            Error::__IsNotComplete => io::ErrorKind::Other,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::NotATty => write!(f, "Not a terminal."),
+            Error::QueryTimeout { ref query } => {
+                write!(f, "The terminal did not answer the {} query in time.", query)
+            }
+            Error::MalformedResponse { ref bytes } => {
+                write!(f, "Could not parse the bytes sent by the terminal: {:?}.",
+                       String::from_utf8_lossy(bytes))
+            }
+            Error::InvalidUtf8 { ref bytes } => {
+                write!(f, "The terminal sent invalid UTF-8: {:?}.", String::from_utf8_lossy(bytes))
+            }
+            Error::InvalidTerminfo { ref reason } => write!(f, "Invalid terminfo entry: {}.", reason),
+            Error::UnknownTerminal { ref name } => {
+                write!(f, "There is no terminfo entry for the {:?} terminal.", name)
+            }
+            Error::MissingCapability { ref name } => {
+                write!(f, "The terminal lacks the {:?} capability.", name)
+            }
+            Error::InvalidColor { ref text } => write!(f, "Invalid color: {:?}.", text),
+            Error::Io(ref e) => e.fmt(f),
+            Error::__IsNotComplete => Ok(()),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    /// Get back the termion error wrapped in an `io::Error`, or wrap other I/O errors.
+    fn from(err: io::Error) -> Error {
+        err.downcast::<Error>().unwrap_or_else(Error::Io)
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(err: Error) -> io::Error {
+        match err {
+            Error::Io(e) => e,
+            err => io::Error::new(err.kind(), err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_io_conversion() {
+        let err = io::Error::from(Error::QueryTimeout { query: "cursor position".to_owned() });
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        match Error::from(err) {
+            Error::QueryTimeout { query } => assert_eq!(query, "cursor position"),
+            err => panic!("unexpected error: {:?}", err),
+        }
+
+        let err = io::Error::from(Error::Io(io::ErrorKind::BrokenPipe.into()));
+        assert!(err.get_ref().is_none());
+        match Error::from(err) {
+            Error::Io(err) => assert_eq!(err.kind(), io::ErrorKind::BrokenPipe),
+            err => panic!("unexpected error: {:?}", err),
+        }
+
+        let err = io::Error::new(io::ErrorKind::InvalidData, "not a termion error");
+        match Error::from(err) {
+            Error::Io(err) => {
+                assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+                assert_eq!(err.to_string(), "not a termion error");
+            }
+            err => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
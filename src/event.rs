@@ -1,18 +1,30 @@
 //! Mouse and key events.
 
-use std::io::{Error, ErrorKind};
-use std::ascii::AsciiExt;
+use std::io;
 use std::str;
 
+use error::Error;
+
 /// An event reported by the terminal.
+///
+/// More events may be added: `match`es on events need a wildcard arm.
+#[allow(clippy::manual_non_exhaustive)]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Event {
     /// A key press.
     Key(Key),
     /// A mouse button press, release or wheel use at specific coordinates.
     Mouse(MouseEvent),
+    /// The terminal was resized to the given (width, height).
+    ///
+    /// This is never parsed from input; it is reported by the event sources which watch for
+    /// window size changes.
+    Resize(u16, u16),
     /// An event that cannot currently be evaluated.
     Unsupported(Vec<u8>),
+
+    #[doc(hidden)]
+    __IsNotComplete,
 }
 
 /// A mouse related event.
@@ -52,6 +64,7 @@ pub enum MouseButton {
 }
 
 /// A key.
+#[allow(clippy::manual_non_exhaustive)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Key {
     /// Backspace.
@@ -98,10 +111,35 @@ pub enum Key {
 }
 
 /// Parse an Event from `item` and possibly subsequent bytes through `iter`.
-pub fn parse_event<I>(item: u8, iter: &mut I) -> Result<Event, Error>
-    where I: Iterator<Item = Result<u8, Error>>
+///
+/// Sequences which cannot be parsed are reported as `Error::MalformedResponse`, and characters
+/// which are not valid UTF-8 as `Error::InvalidUtf8`, with the bytes read.
+pub fn parse_event<I>(item: u8, iter: &mut I) -> Result<Event, io::Error>
+    where I: Iterator<Item = Result<u8, io::Error>>
 {
-    let error = Error::new(ErrorKind::Other, "Could not parse an event");
+    let mut bytes = vec![item];
+    let res = {
+        let mut iter = iter.inspect(|byte| if let Ok(byte) = *byte {
+                                        bytes.push(byte);
+                                    });
+        parse_event_bytes(item, &mut iter)
+    };
+
+    res.map_err(|err| {
+        match err {
+                Error::MalformedResponse { .. } => Error::MalformedResponse { bytes },
+                Error::InvalidUtf8 { .. } => Error::InvalidUtf8 { bytes },
+                err => err,
+            }
+            .into()
+    })
+}
+
+/// Parse an event, leaving the bytes of the errors empty.
+fn parse_event_bytes<I>(item: u8, iter: &mut I) -> Result<Event, Error>
+    where I: Iterator<Item = Result<u8, io::Error>>
+{
+    let error = Error::MalformedResponse { bytes: Vec::new() };
     match item {
         b'\x1B' => {
             // This is an escape character, leading a control sequence.
@@ -109,7 +147,7 @@ pub fn parse_event<I>(item: u8, iter: &mut I) -> Result<Event, Error>
                    Some(Ok(b'O')) => {
                 match iter.next() {
                     // F1-F4
-                    Some(Ok(val @ b'P'...b'S')) => Event::Key(Key::F(1 + val - b'P')),
+                    Some(Ok(val @ b'P'..=b'S')) => Event::Key(Key::F(1 + val - b'P')),
                     _ => return Err(error),
                 }
             }
@@ -119,7 +157,7 @@ pub fn parse_event<I>(item: u8, iter: &mut I) -> Result<Event, Error>
             }
                    Some(Ok(c)) => {
                 let ch = parse_utf8_char(c, iter);
-                Event::Key(Key::Alt(try!(ch)))
+                Event::Key(Key::Alt(ch?))
             }
                    Some(Err(_)) | None => return Err(error),
                })
@@ -127,13 +165,13 @@ pub fn parse_event<I>(item: u8, iter: &mut I) -> Result<Event, Error>
         b'\n' | b'\r' => Ok(Event::Key(Key::Char('\n'))),
         b'\t' => Ok(Event::Key(Key::Char('\t'))),
         b'\x7F' => Ok(Event::Key(Key::Backspace)),
-        c @ b'\x01'...b'\x1A' => Ok(Event::Key(Key::Ctrl((c as u8 - 0x1 + b'a') as char))),
-        c @ b'\x1C'...b'\x1F' => Ok(Event::Key(Key::Ctrl((c as u8 - 0x1C + b'4') as char))),
+        c @ b'\x01'..=b'\x1A' => Ok(Event::Key(Key::Ctrl((c - 0x1 + b'a') as char))),
+        c @ b'\x1C'..=b'\x1F' => Ok(Event::Key(Key::Ctrl((c - 0x1C + b'4') as char))),
         b'\0' => Ok(Event::Key(Key::Null)),
         c => {
             Ok({
                    let ch = parse_utf8_char(c, iter);
-                   Event::Key(Key::Char(try!(ch)))
+                   Event::Key(Key::Char(ch?))
                })
         }
     }
@@ -143,11 +181,11 @@ pub fn parse_event<I>(item: u8, iter: &mut I) -> Result<Event, Error>
 ///
 /// Returns None if an unrecognized sequence is found.
 fn parse_csi<I>(iter: &mut I) -> Option<Event>
-    where I: Iterator<Item = Result<u8, Error>>
+    where I: Iterator<Item = Result<u8, io::Error>>
 {
     Some(match iter.next() {
              Some(Ok(b'[')) => match iter.next() {
-                 Some(Ok(val @ b'A'...b'E')) => Event::Key(Key::F(1 + val - b'A')),
+                 Some(Ok(val @ b'A'..=b'E')) => Event::Key(Key::F(1 + val - b'A')),
                  _ => return None,
              },
              Some(Ok(b'D')) => Event::Key(Key::Left),
@@ -158,12 +196,12 @@ fn parse_csi<I>(iter: &mut I) -> Option<Event>
              Some(Ok(b'F')) => Event::Key(Key::End),
              Some(Ok(b'M')) => {
         // X10 emulation mouse encoding: ESC [ CB Cx Cy (6 characters only).
-        let mut next = || iter.next().unwrap().unwrap();
+        let mut next = || next_byte(iter);
 
-        let cb = next() as i8 - 32;
+        let cb = next()? as i8 - 32;
         // (1, 1) are the coords for upper left.
-        let cx = next().saturating_sub(32) as u16;
-        let cy = next().saturating_sub(32) as u16;
+        let cx = next()?.saturating_sub(32) as u16;
+        let cy = next()?.saturating_sub(32) as u16;
         Event::Mouse(match cb & 0b11 {
                          0 => {
                              if cb & 0x40 != 0 {
@@ -188,32 +226,26 @@ fn parse_csi<I>(iter: &mut I) -> Option<Event>
         // xterm mouse encoding:
         // ESC [ < Cb ; Cx ; Cy (;) (M or m)
         let mut buf = Vec::new();
-        let mut c = iter.next().unwrap().unwrap();
-        while match c {
-                  b'm' | b'M' => false,
-                  _ => true,
-              } {
+        let mut c = next_byte(iter)?;
+        while !matches!(c, b'm' | b'M') {
             buf.push(c);
-            c = iter.next().unwrap().unwrap();
+            c = next_byte(iter)?;
         }
-        let str_buf = String::from_utf8(buf).unwrap();
+        let str_buf = String::from_utf8(buf).ok()?;
         let nums = &mut str_buf.split(';');
 
-        let cb = nums.next()
-            .unwrap()
+        let cb = nums.next()?
             .parse::<u16>()
-            .unwrap();
-        let cx = nums.next()
-            .unwrap()
+            .ok()?;
+        let cx = nums.next()?
             .parse::<u16>()
-            .unwrap();
-        let cy = nums.next()
-            .unwrap()
+            .ok()?;
+        let cy = nums.next()?
             .parse::<u16>()
-            .unwrap();
+            .ok()?;
 
         let event = match cb {
-            0...2 | 64...65 => {
+            0..=2 | 64..=65 => {
                 let button = match cb {
                     0 => MouseButton::Left,
                     1 => MouseButton::Middle,
@@ -235,25 +267,29 @@ fn parse_csi<I>(iter: &mut I) -> Option<Event>
 
         Event::Mouse(event)
     }
-             Some(Ok(c @ b'0'...b'9')) => {
+             Some(Ok(c @ b'0'..=b'9')) => {
         // Numbered escape code.
         let mut buf = Vec::new();
         buf.push(c);
-        let mut c = iter.next().unwrap().unwrap();
+        let mut c = next_byte(iter)?;
         // The final byte of a CSI sequence can be in the range 64-126, so
         // let's keep reading anything else.
-        while c < 64 || c > 126 {
+        while !(64..=126).contains(&c) {
             buf.push(c);
-            c = iter.next().unwrap().unwrap();
+            c = next_byte(iter)?;
         }
 
         match c {
             // rxvt mouse encoding:
             // ESC [ Cb ; Cx ; Cy ; M
             b'M' => {
-                let str_buf = String::from_utf8(buf).unwrap();
+                let str_buf = String::from_utf8(buf).ok()?;
 
-                let nums: Vec<u16> = str_buf.split(';').map(|n| n.parse().unwrap()).collect();
+                let nums = str_buf.split(';').map(|n| n.parse().ok());
+                let nums: Vec<u16> = nums.collect::<Option<_>>()?;
+                if nums.len() < 3 {
+                    return None;
+                }
 
                 let cb = nums[0];
                 let cx = nums[1];
@@ -273,11 +309,12 @@ fn parse_csi<I>(iter: &mut I) -> Option<Event>
             }
             // Special key code.
             b'~' => {
-                let str_buf = String::from_utf8(buf).unwrap();
+                let str_buf = String::from_utf8(buf).ok()?;
 
                 // This CSI sequence can be a list of semicolon-separated
                 // numbers.
-                let nums: Vec<u8> = str_buf.split(';').map(|n| n.parse().unwrap()).collect();
+                let nums = str_buf.split(';').map(|n| n.parse().ok());
+                let nums: Vec<u8> = nums.collect::<Option<_>>()?;
 
                 if nums.is_empty() {
                     return None;
@@ -296,9 +333,9 @@ fn parse_csi<I>(iter: &mut I) -> Option<Event>
                     4 | 8 => Event::Key(Key::End),
                     5 => Event::Key(Key::PageUp),
                     6 => Event::Key(Key::PageDown),
-                    v @ 11...15 => Event::Key(Key::F(v - 10)),
-                    v @ 17...21 => Event::Key(Key::F(v - 11)),
-                    v @ 23...24 => Event::Key(Key::F(v - 12)),
+                    v @ 11..=15 => Event::Key(Key::F(v - 10)),
+                    v @ 17..=21 => Event::Key(Key::F(v - 11)),
+                    v @ 23..=24 => Event::Key(Key::F(v - 12)),
                     _ => return None,
                 }
             }
@@ -310,11 +347,21 @@ fn parse_csi<I>(iter: &mut I) -> Option<Event>
 
 }
 
+/// Get the next byte of a sequence, or `None` if the input ended or failed.
+fn next_byte<I>(iter: &mut I) -> Option<u8>
+    where I: Iterator<Item = Result<u8, io::Error>>
+{
+    match iter.next() {
+        Some(Ok(byte)) => Some(byte),
+        _ => None,
+    }
+}
+
 /// Parse `c` as either a single byte ASCII char or a variable size UTF-8 char.
 fn parse_utf8_char<I>(c: u8, iter: &mut I) -> Result<char, Error>
-    where I: Iterator<Item = Result<u8, Error>>
+    where I: Iterator<Item = Result<u8, io::Error>>
 {
-    let error = Err(Error::new(ErrorKind::Other, "Input character is not valid UTF-8"));
+    let error = Err(Error::InvalidUtf8 { bytes: Vec::new() });
     if c.is_ascii() {
         Ok(c as char)
     } else {
@@ -338,11 +385,27 @@ fn parse_utf8_char<I>(c: u8, iter: &mut I) -> Result<char, Error>
     }
 }
 
+#[cfg(test)]
+#[test]
+fn test_parse_errors() {
+    let bytes = &mut b"[<0;x;4M".iter().map(|&b| Ok(b));
+    match Error::from(parse_event(b'\x1B', bytes).unwrap_err()) {
+        Error::MalformedResponse { bytes } => assert_eq!(bytes, b"\x1B[<0;x;4M"),
+        err => panic!("unexpected error: {:?}", err),
+    }
+
+    let bytes = &mut b"(".iter().map(|&b| Ok(b));
+    match Error::from(parse_event(b'\xC3', bytes).unwrap_err()) {
+        Error::InvalidUtf8 { bytes } => assert_eq!(bytes, b"\xC3("),
+        err => panic!("unexpected error: {:?}", err),
+    }
+}
+
 #[cfg(test)]
 #[test]
 fn test_parse_utf8() {
     let st = "abcéŷ¤£€ù%323";
-    let ref mut bytes = st.bytes().map(|x| Ok(x));
+    let bytes = &mut st.bytes().map(Ok);
     let chars = st.chars();
     for c in chars {
         let b = bytes.next().unwrap().unwrap();
@@ -0,0 +1,282 @@
+//! A terminal event loop.
+//!
+//! `EventLoop` waits on the TTY with `poll(2)`, and merges its key and mouse events with window
+//! resizes, periodic ticks and events sent from other threads. Nothing is spent spinning, and
+//! input is delivered as soon as it arrives.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use std::thread;
+//! use std::time::Duration;
+//! use termion::event::{Event, Key};
+//! use termion::event_loop::{EventLoop, LoopEvent};
+//!
+//! fn main() {
+//!     let mut events = EventLoop::new().unwrap();
+//!     events.set_tick_rate(Some(Duration::from_millis(1000 / 30)));
+//!
+//!     let sender = events.sender();
+//!     thread::spawn(move || sender.send("Hello from another thread.").unwrap());
+//!
+//!     for event in events {
+//!         match event.unwrap() {
+//!             LoopEvent::Input(Event::Key(Key::Char('q'))) => break,
+//!             LoopEvent::Input(event) => println!("{:?}", event),
+//!             LoopEvent::User(message) => println!("{}", message),
+//!             LoopEvent::Tick => {}
+//!         }
+//!     }
+//! }
+//! ```
+
+use std::fs;
+use std::io::{self, Read};
+use std::os::unix::io::AsRawFd;
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
+use event::Event;
+use input::EventBuffer;
+use sys::poll::poll;
+use restore::resume_all;
+use sys::signal::{Pipe, SignalPipe, SIGCONT, SIGWINCH};
+use sys::size::terminal_size;
+use sys::tty::{get_tty, set_nonblocking};
+
+/// An event delivered by the event loop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoopEvent<T> {
+    /// An event reported by the terminal, including window resizes.
+    Input(Event),
+    /// The tick timer fired.
+    Tick,
+    /// An event sent through a `Sender`.
+    User(T),
+}
+
+/// A handle for sending events to an `EventLoop` from any thread.
+pub struct Sender<T> {
+    send: mpsc::Sender<T>,
+    wake: Arc<Pipe>,
+}
+
+impl<T> Sender<T> {
+    /// Send an event, waking up the loop.
+    ///
+    /// This fails, giving back the event, if the loop has been dropped.
+    pub fn send(&self, event: T) -> Result<(), mpsc::SendError<T>> {
+        self.send.send(event)?;
+        self.wake.wake(0);
+        Ok(())
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Sender<T> {
+        Sender {
+            send: self.send.clone(),
+            wake: self.wake.clone(),
+        }
+    }
+}
+
+/// An event loop over the TTY.
+///
+/// Events are read through the `Iterator` implementation, which blocks until the next event. It
+/// ends when the TTY reaches end of file.
+///
+/// When the program is continued after being stopped, the terminal modes are set up again and an
+/// `Event::Resize` is reported, so the screen can be redrawn.
+pub struct EventLoop<T> {
+    tty: fs::File,
+    buffer: EventBuffer,
+    signals: SignalPipe,
+    wake: Arc<Pipe>,
+    send: mpsc::Sender<T>,
+    recv: mpsc::Receiver<T>,
+    tick_rate: Option<Duration>,
+    next_tick: Instant,
+    eof: bool,
+    was_nonblocking: bool,
+}
+
+impl<T> EventLoop<T> {
+    /// Open the TTY device and create an event loop over it.
+    pub fn new() -> io::Result<EventLoop<T>> {
+        EventLoop::from_tty(get_tty()?)
+    }
+
+    /// Create an event loop reading from the given terminal device.
+    ///
+    /// The file is switched to non-blocking mode, until the event loop is dropped.
+    pub fn from_tty(tty: fs::File) -> io::Result<EventLoop<T>> {
+        let was_nonblocking = set_nonblocking(tty.as_raw_fd(), true)?;
+        let (send, recv) = mpsc::channel();
+
+        Ok(EventLoop {
+            tty,
+            buffer: EventBuffer::new(),
+            signals: SignalPipe::new(&[SIGWINCH, SIGCONT])?,
+            wake: Arc::new(Pipe::new()?),
+            send,
+            recv,
+            tick_rate: None,
+            next_tick: Instant::now(),
+            eof: false,
+            was_nonblocking,
+        })
+    }
+
+    /// Set the interval between `LoopEvent::Tick` events, or disable them with `None`.
+    ///
+    /// For a frame rate of `n` per second, use `Duration::from_secs(1) / n`.
+    pub fn set_tick_rate(&mut self, rate: Option<Duration>) {
+        self.tick_rate = rate;
+        if let Some(rate) = rate {
+            self.next_tick = Instant::now() + rate;
+        }
+    }
+
+    /// Get a handle for sending events to this loop.
+    pub fn sender(&self) -> Sender<T> {
+        Sender {
+            send: self.send.clone(),
+            wake: self.wake.clone(),
+        }
+    }
+
+    /// Read everything the TTY has available into the buffer.
+    fn read_tty(&mut self) -> io::Result<()> {
+        let mut buf = [0; 1024];
+        loop {
+            match self.tty.read(&mut buf) {
+                Ok(0) => {
+                    self.eof = true;
+                    return Ok(());
+                }
+                Ok(n) => self.buffer.extend(&buf[..n]),
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Check whether the tick timer is due, and schedule the next one if it is.
+    fn tick(&mut self) -> bool {
+        let rate = match self.tick_rate {
+            Some(rate) => rate,
+            None => return false,
+        };
+
+        let now = Instant::now();
+        if now < self.next_tick {
+            return false;
+        }
+
+        self.next_tick += rate;
+        if self.next_tick < now {
+            // We fell behind; skip the missed ticks rather than firing them in a burst.
+            self.next_tick = now + rate;
+        }
+        true
+    }
+
+    /// Wait for the next event.
+    fn next_event(&mut self) -> Option<io::Result<LoopEvent<T>>> {
+        loop {
+            // The TTY is always drained, so there is no more input to wait for.
+            if let Some((event, _)) = self.buffer.next_event(true) {
+                return Some(Ok(LoopEvent::Input(event)));
+            }
+
+            if let Ok(event) = self.recv.try_recv() {
+                return Some(Ok(LoopEvent::User(event)));
+            }
+
+            let signals = self.signals.drain();
+            if !signals.is_empty() {
+                if signals.contains(&SIGCONT) {
+                    // The terminal may have been changed while the program was stopped.
+                    resume_all();
+                }
+                let event = terminal_size().map(|(w, h)| LoopEvent::Input(Event::Resize(w, h)));
+                return Some(event);
+            }
+
+            if self.tick() {
+                return Some(Ok(LoopEvent::Tick));
+            }
+
+            if self.eof {
+                return None;
+            }
+
+            let next_tick = self.next_tick;
+            let timeout = self.tick_rate.map(|_| next_tick.saturating_duration_since(Instant::now()));
+
+            let fds = [self.tty.as_raw_fd(), self.signals.fd(), self.wake.fd()];
+            let ready = match poll(&fds, timeout) {
+                Ok(ready) => ready,
+                Err(e) => return Some(Err(e)),
+            };
+
+            if ready[0] {
+                if let Err(e) = self.read_tty() {
+                    return Some(Err(e));
+                }
+            }
+            if ready[2] {
+                self.wake.drain();
+            }
+        }
+    }
+}
+
+impl<T> Drop for EventLoop<T> {
+    fn drop(&mut self) {
+        let _ = set_nonblocking(self.tty.as_raw_fd(), self.was_nonblocking);
+    }
+}
+
+impl<T> Iterator for EventLoop<T> {
+    type Item = io::Result<LoopEvent<T>>;
+
+    fn next(&mut self) -> Option<io::Result<LoopEvent<T>>> {
+        self.next_event()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::thread;
+    use sys::tty::{open_pty, open_tty};
+
+    #[test]
+    fn test_user_events_and_ticks() {
+        let (_master, path) = open_pty().unwrap();
+        let mut events = EventLoop::from_tty(open_tty(&path).unwrap()).unwrap();
+
+        let sender = events.sender();
+        thread::spawn(move || sender.send(42).unwrap()).join().unwrap();
+        assert_eq!(events.next().unwrap().unwrap(), LoopEvent::User(42));
+
+        let start = Instant::now();
+        events.set_tick_rate(Some(Duration::from_millis(10)));
+        assert_eq!(events.next().unwrap().unwrap(), LoopEvent::Tick);
+        assert!(start.elapsed() >= Duration::from_millis(10));
+    }
+
+    #[test]
+    fn test_restore_blocking_mode() {
+        let (_master, path) = open_pty().unwrap();
+        let tty = open_tty(&path).unwrap();
+        // The duplicate shares the file status flags, and outlives the loop.
+        let dup = tty.try_clone().unwrap();
+
+        drop(EventLoop::<()>::from_tty(tty).unwrap());
+        assert!(!set_nonblocking(dup.as_raw_fd(), false).unwrap());
+    }
+}
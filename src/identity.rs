@@ -0,0 +1,303 @@
+//! Identifying the terminal emulator.
+//!
+//! `TERM` is often set to `xterm-256color` whatever the emulator is, so the terminal itself has to
+//! be asked. `query_terminal_identity` sends the Primary and Secondary Device Attributes requests
+//! (DA1 and DA2) and XTVERSION, and collects the answers in a `TerminalIdentity`.
+//!
+//! The terminal must be in raw mode, so that the answers are not echoed, nor held back until the
+//! end of the line.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use termion::async_stdin;
+//! use termion::identity::{query_terminal_identity, Feature};
+//! use termion::query::DEFAULT_TIMEOUT;
+//! use termion::raw::IntoRawMode;
+//! use std::io::stdout;
+//!
+//! fn main() {
+//!     let mut stdout = stdout().into_raw_mode().unwrap();
+//!     let mut stdin = async_stdin();
+//!
+//!     match query_terminal_identity(&mut stdout, &mut stdin, DEFAULT_TIMEOUT).unwrap() {
+//!         Some(identity) => {
+//!             println!("{:?}\r", identity.name);
+//!             println!("Sixel graphics: {}\r", identity.supports(Feature::Sixel));
+//!         }
+//!         None => println!("The terminal did not answer.\r"),
+//!     }
+//! }
+//! ```
+
+use std::io::{self, Read, Write};
+use std::ops::Range;
+use std::str;
+use std::time::Duration;
+
+use error::Error;
+use query::{find_sequence, find_string, query_many, Unread};
+
+/// A feature reported in the Primary Device Attributes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Feature {
+    /// 132 columns.
+    Columns132,
+    /// A printer port.
+    Printer,
+    /// ReGIS graphics.
+    Regis,
+    /// Sixel graphics.
+    Sixel,
+    /// Selective erase.
+    SelectiveErase,
+    /// User-defined keys.
+    UserDefinedKeys,
+    /// National replacement character sets.
+    NationalReplacementCharsets,
+    /// Technical characters.
+    TechnicalCharacters,
+    /// Locator port.
+    Locator,
+    /// Terminal state interrogation.
+    TerminalStateInterrogation,
+    /// Windowing capability.
+    Windowing,
+    /// Horizontal scrolling.
+    HorizontalScrolling,
+    /// ANSI color.
+    AnsiColor,
+    /// Rectangular editing.
+    RectangularEditing,
+    /// ANSI text locator.
+    AnsiTextLocator,
+    /// Any other attribute, with its number.
+    Other(u16),
+}
+
+impl Feature {
+    fn from_code(code: u16) -> Feature {
+        match code {
+            1 => Feature::Columns132,
+            2 => Feature::Printer,
+            3 => Feature::Regis,
+            4 => Feature::Sixel,
+            6 => Feature::SelectiveErase,
+            8 => Feature::UserDefinedKeys,
+            9 => Feature::NationalReplacementCharsets,
+            15 => Feature::TechnicalCharacters,
+            16 => Feature::Locator,
+            17 => Feature::TerminalStateInterrogation,
+            18 => Feature::Windowing,
+            21 => Feature::HorizontalScrolling,
+            22 => Feature::AnsiColor,
+            28 => Feature::RectangularEditing,
+            29 => Feature::AnsiTextLocator,
+            code => Feature::Other(code),
+        }
+    }
+}
+
+/// What the terminal says about itself.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TerminalIdentity {
+    /// The conformance level from the Primary Device Attributes, 1 for a VT100 and up to 5 for a
+    /// VT500.
+    pub conformance_level: u16,
+    /// The features from the Primary Device Attributes.
+    pub features: Vec<Feature>,
+    /// The terminal type from the Secondary Device Attributes, if they were answered.
+    ///
+    /// This is a VT model number (for example 1 for a VT220, or 41 for a VT420) which many
+    /// emulators use for their own purposes.
+    pub terminal_type: Option<u16>,
+    /// The firmware version from the Secondary Device Attributes, if they were answered.
+    pub firmware_version: Option<u16>,
+    /// The name of the emulator, from XTVERSION, if it was answered.
+    pub name: Option<String>,
+    /// The version of the emulator, from XTVERSION, if it was answered and has one.
+    pub version: Option<String>,
+}
+
+impl TerminalIdentity {
+    /// Check whether the terminal reported a feature.
+    pub fn supports(&self, feature: Feature) -> bool {
+        self.features.contains(&feature)
+    }
+}
+
+/// Ask the terminal to identify itself, reading the answers from `reader`.
+///
+/// This returns `None` if the terminal did not answer within `timeout`, as dumb terminals do.
+/// The other input read meanwhile is put back into `reader`. See the `query` module.
+pub fn query_terminal_identity<W, R>(writer: &mut W,
+                                     reader: &mut R,
+                                     timeout: Duration)
+                                     -> io::Result<Option<TerminalIdentity>>
+    where W: Write + ?Sized,
+          R: Read + Unread + ?Sized
+{
+    // Terminals answer in order, and nearly all of them answer DA1, so it is asked last: once it
+    // is answered, the requests which were not will never be.
+    // XTVERSION is `CSI > 0 q`, answered with `DCS > | text ST`.
+    // DA2 is `CSI > c`, answered with `CSI > Pp ; Pv ; Pc c`.
+    // DA1 is `CSI c`, answered with `CSI ? Pp ; Ps... c`.
+    let res = query_many(writer, reader, "\x1B[>0q\x1B[>c\x1B[c", timeout, |input| {
+        let is_param = |b: u8| b.is_ascii_digit() || b == b';';
+        let (da1, da1_params) = find_sequence(input, b"\x1B[?", is_param, b"c")?;
+
+        let before = &input[..da1.start];
+        let version = find_string(before, b"\x1BP>|");
+        let da2 = find_sequence(before, b"\x1B[>", is_param, b"c");
+
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        ranges.extend(version.iter().map(|(range, _)| range.clone()));
+        ranges.extend(da2.iter().map(|(range, _)| range.clone()));
+        ranges.sort_by_key(|range| range.start);
+        ranges.push(da1.clone());
+
+        let malformed = |range: &Range<usize>| {
+            Error::MalformedResponse { bytes: input[range.clone()].to_vec() }
+        };
+
+        let res = parse_params(&input[da1_params])
+            .filter(|params| !params.is_empty())
+            .ok_or_else(|| malformed(&da1))
+            .and_then(|da1_params| {
+                let mut identity = parse_da1(&da1_params);
+
+                if let Some((ref range, ref params)) = da2 {
+                    let params = parse_params(&input[params.clone()])
+                        .ok_or_else(|| malformed(range))?;
+                    identity.terminal_type = params.first().cloned();
+                    identity.firmware_version = params.get(1).cloned();
+                }
+
+                if let Some((ref range, ref text)) = version {
+                    let text = str::from_utf8(&input[text.clone()])
+                        .map_err(|_| Error::InvalidUtf8 { bytes: input[range.clone()].to_vec() })?;
+                    let (name, version) = parse_version(text);
+                    identity.name = Some(name);
+                    identity.version = version;
+                }
+
+                Ok(identity)
+            });
+
+        Some((res, ranges))
+    });
+
+    match res.map_err(Error::from) {
+        Ok(identity) => Ok(Some(identity)),
+        Err(Error::QueryTimeout { .. }) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Parse semicolon separated numbers. Empty numbers are zero.
+fn parse_params(params: &[u8]) -> Option<Vec<u16>> {
+    str::from_utf8(params)
+        .ok()?
+        .split(';')
+        .map(|n| if n.is_empty() { Some(0) } else { n.parse().ok() })
+        .collect()
+}
+
+/// Build an identity from the Primary Device Attributes.
+fn parse_da1(params: &[u16]) -> TerminalIdentity {
+    let (level, features) = match params[0] {
+        // A VT100 or VT102, with options which are not features.
+        1 | 6 => (1, Vec::new()),
+        // A VT200 to VT500, with features.
+        class @ 62..=69 => {
+            (class - 60, params[1..].iter().map(|&p| Feature::from_code(p)).collect())
+        }
+        _ => (1, Vec::new()),
+    };
+
+    TerminalIdentity {
+        conformance_level: level,
+        features,
+        ..TerminalIdentity::default()
+    }
+}
+
+/// Split the XTVERSION text, such as `XTerm(388)` or `tmux 3.4`, into a name and a version.
+fn parse_version(text: &str) -> (String, Option<String>) {
+    if let Some(text) = text.strip_suffix(')') {
+        if let Some(open) = text.find('(') {
+            return (text[..open].to_owned(), Some(text[open + 1..].to_owned()));
+        }
+    }
+
+    match text.find(' ') {
+        Some(space) => (text[..space].to_owned(), Some(text[space + 1..].trim().to_owned())),
+        None => (text.to_owned(), None),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use query::Pushback;
+    use std::time::Duration;
+
+    fn identify(input: &[u8]) -> (Option<TerminalIdentity>, Vec<u8>) {
+        let mut reader = Pushback::new(input);
+        let identity = query_terminal_identity(&mut Vec::new(),
+                                               &mut reader,
+                                               Duration::from_millis(10))
+            .unwrap();
+
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        (identity, rest)
+    }
+
+    #[test]
+    fn test_xterm() {
+        let (identity, rest) =
+            identify(b"\x1BP>|XTerm(388)\x1B\\a\x1B[>41;388;0c\x1B[?64;1;2;4;6;9;15;18;21;22cb");
+        let identity = identity.unwrap();
+
+        assert_eq!(identity.conformance_level, 4);
+        assert!(identity.supports(Feature::Sixel));
+        assert!(identity.supports(Feature::AnsiColor));
+        assert!(!identity.supports(Feature::Regis));
+        assert_eq!(identity.terminal_type, Some(41));
+        assert_eq!(identity.firmware_version, Some(388));
+        assert_eq!(identity.name, Some("XTerm".to_owned()));
+        assert_eq!(identity.version, Some("388".to_owned()));
+        assert_eq!(rest, b"ab");
+    }
+
+    #[test]
+    fn test_vt100() {
+        let (identity, rest) = identify(b"\x1B[?1;2c");
+        let identity = identity.unwrap();
+
+        assert_eq!(identity.conformance_level, 1);
+        assert!(identity.features.is_empty());
+        assert_eq!(identity.terminal_type, None);
+        assert_eq!(identity.name, None);
+        assert_eq!(rest, b"");
+    }
+
+    #[test]
+    fn test_tmux() {
+        let (identity, _) = identify(b"\x1BP>|tmux 3.4\x1B\\\x1B[>84;0;0c\x1B[?1;2;4c");
+        let identity = identity.unwrap();
+
+        assert_eq!(identity.name, Some("tmux".to_owned()));
+        assert_eq!(identity.version, Some("3.4".to_owned()));
+        assert_eq!(identity.terminal_type, Some(84));
+    }
+
+    #[test]
+    fn test_dumb_terminal() {
+        let (identity, rest) = identify(b"typed");
+
+        assert_eq!(identity, None);
+        assert_eq!(rest, b"typed");
+    }
+}
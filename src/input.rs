@@ -2,9 +2,12 @@
 
 use std::io::{self, Read, Write};
 use std::ops;
+use std::os::unix::io::{AsRawFd, RawFd};
 
+use error::Error;
 use event::{self, Event, Key};
-use raw::IntoRawMode;
+use raw::RawTerminal;
+use restore::{self, Mode};
 
 /// An iterator over input keys.
 pub struct Keys<R> {
@@ -48,8 +51,9 @@ pub struct EventsAndRaw<R> {
 impl<R: Read> Iterator for EventsAndRaw<R> {
     type Item = Result<(Event, Vec<u8>), io::Error>;
 
+    #[allow(clippy::unbuffered_bytes)]
     fn next(&mut self) -> Option<Result<(Event, Vec<u8>), io::Error>> {
-        let mut source = &mut self.source;
+        let source = &mut self.source;
 
         if let Some(c) = self.leftover {
             // we have a leftover byte, use it
@@ -71,9 +75,9 @@ impl<R: Read> Iterator for EventsAndRaw<R> {
                 }
             }
             Ok(2) => {
-                let mut option_iter = &mut Some(buf[1]).into_iter();
+                let option_iter = &mut Some(buf[1]).into_iter();
                 let result = {
-                    let mut iter = option_iter.map(|c| Ok(c)).chain(source.bytes());
+                    let mut iter = option_iter.map(Ok).chain(source.bytes());
                     parse_event(buf[0], &mut iter)
                 };
                 // If the option_iter wasn't consumed, keep the byte for later.
@@ -101,6 +105,66 @@ fn parse_event<I>(item: u8, iter: &mut I) -> Result<(Event, Vec<u8>), io::Error>
     result.or(Ok(Event::Unsupported(buf.clone()))).map(|e| (e, buf))
 }
 
+/// A buffer decoding events from bytes which arrive in arbitrary chunks.
+///
+/// Unlike `EventsAndRaw`, this never blocks waiting for the rest of a sequence. Incomplete
+/// sequences are kept in the buffer until more bytes are fed in.
+#[cfg(not(target_os = "redox"))]
+pub(crate) struct EventBuffer {
+    buf: Vec<u8>,
+}
+
+#[cfg(not(target_os = "redox"))]
+impl EventBuffer {
+    pub fn new() -> EventBuffer {
+        EventBuffer { buf: Vec::new() }
+    }
+
+    /// Append bytes read from the terminal.
+    pub fn extend(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// Decode the next event and the bytes that define it.
+    ///
+    /// `idle` tells that no more input is immediately available, in which case a lone ESC is the
+    /// escape key rather than the start of a sequence.
+    pub fn next_event(&mut self, idle: bool) -> Option<(Event, Vec<u8>)> {
+        let (&first, rest) = self.buf.split_first()?;
+
+        if first == b'\x1B' && rest.is_empty() {
+            if !idle {
+                return None;
+            }
+            self.buf.clear();
+            return Some((Event::Key(Key::Esc), vec![b'\x1B']));
+        }
+
+        let mut used = 0;
+        let mut ran_out = false;
+        let result = {
+            let mut iter = ::std::iter::from_fn(|| match rest.get(used) {
+                Some(&byte) => {
+                    used += 1;
+                    Some(Ok(byte))
+                }
+                None => {
+                    ran_out = true;
+                    None
+                }
+            });
+            event::parse_event(first, &mut iter)
+        };
+
+        if result.is_err() && ran_out {
+            // The sequence is not complete yet.
+            return None;
+        }
+
+        let raw: Vec<u8> = self.buf.drain(..used + 1).collect();
+        Some((result.unwrap_or_else(|_| Event::Unsupported(raw.clone())), raw))
+    }
+}
 
 /// Extension to `Read` trait.
 pub trait TermRead {
@@ -120,8 +184,10 @@ pub trait TermRead {
     ///
     /// EOT and ETX will abort the prompt, returning `None`. Newline or carriage return will
     /// complete the input.
+    ///
+    /// The terminal on standard input is in raw mode while reading, so the password is not echoed.
     fn read_passwd<W: Write>(&mut self, writer: &mut W) -> io::Result<Option<String>> {
-        let _raw = try!(writer.into_raw_mode());
+        let _raw = RawTerminal::new(writer, io::stdin().as_raw_fd())?;
         self.read_line()
     }
 }
@@ -137,6 +203,7 @@ impl<R: Read + TermReadEventsAndRaw> TermRead for R {
         Keys { iter: self.events() }
     }
 
+    #[allow(clippy::unbuffered_bytes)]
     fn read_line(&mut self) -> io::Result<Option<String>> {
         let mut buf = Vec::with_capacity(30);
 
@@ -152,8 +219,8 @@ impl<R: Read + TermReadEventsAndRaw> TermRead for R {
             }
         }
 
-        let string = try!(String::from_utf8(buf)
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)));
+        let string = String::from_utf8(buf)
+            .map_err(|e| Error::InvalidUtf8 { bytes: e.into_bytes() })?;
         Ok(Some(string))
     }
 }
@@ -174,29 +241,118 @@ impl<R: Read> TermReadEventsAndRaw for R {
 }
 
 /// A sequence of escape codes to enable terminal mouse support.
-const ENTER_MOUSE_SEQUENCE: &'static str = csi!("?1000h\x1b[?1002h\x1b[?1015h\x1b[?1006h");
+const ENTER_MOUSE_SEQUENCE: &str = csi!("?1000h\x1b[?1002h\x1b[?1015h\x1b[?1006h");
 
 /// A sequence of escape codes to disable terminal mouse support.
-const EXIT_MOUSE_SEQUENCE: &'static str = csi!("?1006l\x1b[?1015l\x1b[?1002l\x1b[?1000l");
+const EXIT_MOUSE_SEQUENCE: &str = csi!("?1006l\x1b[?1015l\x1b[?1002l\x1b[?1000l");
 
 /// A terminal with added mouse support.
 ///
-/// This can be obtained through the `From` implementations.
+/// This can be obtained through `new`, or the `From` implementations.
 pub struct MouseTerminal<W: Write> {
-    term: W,
+    /// The output, taken by `restore`.
+    term: Option<W>,
+    id: usize,
 }
 
 impl<W: Write> From<W> for MouseTerminal<W> {
-    fn from(mut from: W) -> MouseTerminal<W> {
-        from.write_all(ENTER_MOUSE_SEQUENCE.as_bytes()).unwrap();
+    /// Enable mouse reporting on `from`.
+    ///
+    /// Since the terminal behind the output is unknown, `restore::restore_all` disables mouse
+    /// reporting on the terminal of the most recently entered raw mode, or on the standard
+    /// output. Use `MouseTerminal::new` for outputs with a file descriptor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the escape sequence cannot be written. Use `MouseTerminal::try_from` to handle
+    /// the error instead.
+    fn from(from: W) -> MouseTerminal<W> {
+        MouseTerminal::try_from(from).expect("enable mouse reporting")
+    }
+}
+
+impl<W: Write + AsRawFd> MouseTerminal<W> {
+    /// Enable mouse reporting on the terminal behind `term`, and wrap it.
+    ///
+    /// Unlike `from`, this reports failures to enable reporting, and `restore::restore_all`
+    /// disables it on the terminal behind `term`.
+    pub fn new(term: W) -> io::Result<MouseTerminal<W>> {
+        let fd = term.as_raw_fd();
+        MouseTerminal::with_fd(term, Some(fd))
+    }
+}
 
-        MouseTerminal { term: from }
+impl<W: Write> MouseTerminal<W> {
+    /// Enable mouse reporting on `term`, and wrap it.
+    ///
+    /// Like `from`, this works on outputs without a file descriptor, but it reports failures to
+    /// enable reporting instead of panicking.
+    pub fn try_from(term: W) -> io::Result<MouseTerminal<W>> {
+        MouseTerminal::with_fd(term, None)
+    }
+
+    /// Enable mouse reporting on `term`, whose file descriptor is `fd` if it is known.
+    fn with_fd(mut term: W, fd: Option<RawFd>) -> io::Result<MouseTerminal<W>> {
+        term.write_all(ENTER_MOUSE_SEQUENCE.as_bytes())?;
+        Ok(MouseTerminal {
+            term: Some(term),
+            id: restore::register(Mode::Escape {
+                fd,
+                enter: ENTER_MOUSE_SEQUENCE,
+                exit: EXIT_MOUSE_SEQUENCE,
+            }),
+        })
+    }
+
+    /// Temporarily disable mouse reporting.
+    pub fn suspend_mouse(&mut self) -> io::Result<()> {
+        self.write_all(EXIT_MOUSE_SEQUENCE.as_bytes())?;
+        self.flush()?;
+        restore::set_active(self.id, false);
+        Ok(())
+    }
+
+    /// Enable mouse reporting again after `suspend_mouse`.
+    pub fn activate_mouse(&mut self) -> io::Result<()> {
+        self.write_all(ENTER_MOUSE_SEQUENCE.as_bytes())?;
+        self.flush()?;
+        restore::set_active(self.id, true);
+        Ok(())
+    }
+
+    /// Run a closure with mouse reporting disabled, enabling it again afterwards.
+    ///
+    /// The closure is given the inner writer, so that the wrappers it may hold can be suspended
+    /// too.
+    pub fn suspended<F, T>(&mut self, f: F) -> io::Result<T>
+        where F: FnOnce(&mut W) -> T
+    {
+        self.suspend_mouse()?;
+        let res = f(&mut **self);
+        self.activate_mouse()?;
+        Ok(res)
+    }
+
+    /// Disable mouse reporting, and get the inner writer back.
+    ///
+    /// Unlike dropping the wrapper, this flushes the output and reports failures to disable
+    /// reporting, in which case the writer is dropped.
+    pub fn restore(mut self) -> io::Result<W> {
+        restore::unregister(self.id);
+        let mut term = self.term.take().expect("the output is only taken when restoring");
+        term.write_all(EXIT_MOUSE_SEQUENCE.as_bytes())?;
+        term.flush()?;
+        Ok(term)
     }
 }
 
 impl<W: Write> Drop for MouseTerminal<W> {
     fn drop(&mut self) {
-        self.term.write_all(EXIT_MOUSE_SEQUENCE.as_bytes()).unwrap();
+        // Errors cannot be reported from here; `restore` does that.
+        if let Some(ref mut term) = self.term {
+            let _ = term.write_all(EXIT_MOUSE_SEQUENCE.as_bytes());
+            restore::unregister(self.id);
+        }
     }
 }
 
@@ -204,23 +360,29 @@ impl<W: Write> ops::Deref for MouseTerminal<W> {
     type Target = W;
 
     fn deref(&self) -> &W {
-        &self.term
+        self.term.as_ref().expect("the output is only taken when restoring")
     }
 }
 
 impl<W: Write> ops::DerefMut for MouseTerminal<W> {
     fn deref_mut(&mut self) -> &mut W {
-        &mut self.term
+        self.term.as_mut().expect("the output is only taken when restoring")
     }
 }
 
 impl<W: Write> Write for MouseTerminal<W> {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.term.write(buf)
+        (**self).write(buf)
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        self.term.flush()
+        (**self).flush()
+    }
+}
+
+impl<W: Write + AsRawFd> AsRawFd for MouseTerminal<W> {
+    fn as_raw_fd(&self) -> RawFd {
+        (**self).as_raw_fd()
     }
 }
 
@@ -276,7 +438,7 @@ mod test {
         let mut output = Vec::<u8>::new();
         {
             let mut i = input.events_and_raw().map(|res| res.unwrap())
-                .inspect(|&(_, ref raw)| { output.extend(raw); }).map(|(event, _)| event);
+                .inspect(|(_, raw)| { output.extend(raw); }).map(|(event, _)| event);
 
             assert_eq!(i.next().unwrap(),
             Event::Unsupported(vec![0x1B, b'[', 0x00]));
@@ -298,7 +460,7 @@ mod test {
             assert!(i.next().is_none());
         }
 
-        assert_eq!(input.iter().map(|b| *b).collect::<Vec<u8>>(), output)
+        assert_eq!(input.to_vec(), output)
     }
 
     #[test]
@@ -337,6 +499,51 @@ mod test {
         assert!(st.next().is_none());
     }
 
+    #[test]
+    #[cfg(not(target_os = "redox"))]
+    fn test_event_buffer() {
+        let mut buf = EventBuffer::new();
+        assert!(buf.next_event(true).is_none());
+
+        buf.extend(b"a\x1B[");
+        assert_eq!(buf.next_event(false).unwrap().0, Event::Key(Key::Char('a')));
+        assert!(buf.next_event(true).is_none());
+
+        buf.extend(b"D\x1B[<0;2;");
+        assert_eq!(buf.next_event(false).unwrap(), (Event::Key(Key::Left), b"\x1B[D".to_vec()));
+        assert!(buf.next_event(false).is_none());
+
+        buf.extend(b"4;M\x1B");
+        assert_eq!(buf.next_event(false).unwrap().0,
+                   Event::Mouse(MouseEvent::Press(MouseButton::Left, 2, 4)));
+        assert!(buf.next_event(false).is_none());
+        assert_eq!(buf.next_event(true).unwrap().0, Event::Key(Key::Esc));
+
+        buf.extend(b"\x1B[\x00\xC3");
+        assert_eq!(buf.next_event(true).unwrap().0,
+                   Event::Unsupported(vec![0x1B, b'[', 0x00]));
+        assert!(buf.next_event(true).is_none());
+        buf.extend(b"\xA9");
+        assert_eq!(buf.next_event(true).unwrap().0, Event::Key(Key::Char('é')));
+        assert!(buf.next_event(true).is_none());
+    }
+
+    #[test]
+    fn test_suspend_mouse() {
+        let mut term = MouseTerminal::from(Vec::new());
+        term.suspended(|out| out.extend(b"x")).unwrap();
+
+        let mut expected = Vec::new();
+        expected.extend(ENTER_MOUSE_SEQUENCE.as_bytes());
+        expected.extend(EXIT_MOUSE_SEQUENCE.as_bytes());
+        expected.extend(b"x");
+        expected.extend(ENTER_MOUSE_SEQUENCE.as_bytes());
+        assert_eq!(*term, expected);
+
+        expected.extend(EXIT_MOUSE_SEQUENCE.as_bytes());
+        assert_eq!(term.restore().unwrap(), expected);
+    }
+
     fn line_match(a: &str, b: Option<&str>) {
         let mut sink = io::sink();
 
@@ -0,0 +1,90 @@
+//! Job control for programs changing the terminal.
+//!
+//! When a program is stopped, for example by typing ctrl-z in the shell, it should hand the
+//! terminal back in its original state, and set it up again once it is continued with `fg`.
+//! Otherwise the shell is left in raw mode on the alternate screen, and the program keeps
+//! running with whatever the shell changed in the meantime.
+//!
+//! `install` handles `SIGTSTP` and `SIGCONT` for every `RawTerminal`, `AlternateScreen` and
+//! `MouseTerminal` alive. In raw mode, ctrl-z does not send `SIGTSTP` but a `Key::Ctrl('z')`
+//! event, which the program can answer by calling `suspend`.
+//!
+//! Once the program is continued, `EventLoop` and `EventStream` report an `Event::Resize`, so the
+//! screen can be redrawn.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use termion::event::Key;
+//! use termion::input::TermRead;
+//! use termion::job_control;
+//! use termion::raw::IntoRawMode;
+//! use std::io::{stdin, stdout};
+//!
+//! fn main() {
+//!     job_control::install().unwrap();
+//!     let _stdout = stdout().into_raw_mode().unwrap();
+//!
+//!     for key in stdin().keys() {
+//!         match key.unwrap() {
+//!             Key::Ctrl('z') => job_control::suspend(),
+//!             Key::Char('q') => break,
+//!             _ => {}
+//!         }
+//!     }
+//! }
+//! ```
+
+use std::io;
+use std::sync::Mutex;
+use std::thread;
+
+use restore::{resume_all, stop_all};
+use sys::poll::poll;
+use sys::signal::{raise_default, SignalPipe, SIGCONT, SIGTSTP};
+
+/// Restore the terminal on `SIGTSTP`, and set it up again on `SIGCONT`.
+///
+/// The signals are handled on a background thread. On `SIGTSTP`, it leaves every active mode and
+/// then stops the program, as if the signal had not been caught. This only has an effect the first
+/// time it is called.
+///
+/// `SIGSTOP` cannot be caught, so a program stopped by it keeps the terminal as it was, but it is
+/// still set up again when the program is continued.
+pub fn install() -> io::Result<()> {
+    static INSTALLED: Mutex<bool> = Mutex::new(false);
+
+    let mut installed = INSTALLED.lock().unwrap_or_else(|e| e.into_inner());
+    if *installed {
+        return Ok(());
+    }
+
+    let pipe = SignalPipe::new(&[SIGTSTP, SIGCONT])?;
+    thread::Builder::new()
+        .name("termion-job-control".to_owned())
+        .spawn(move || loop {
+            if poll(&[pipe.fd()], None).is_err() {
+                return;
+            }
+            for signal in pipe.drain() {
+                if signal == SIGTSTP {
+                    suspend();
+                } else {
+                    resume_all();
+                }
+            }
+        })?;
+
+    *installed = true;
+    Ok(())
+}
+
+/// Stop the program, as the shell does on ctrl-z outside of raw mode.
+///
+/// The terminal is restored before the program is stopped, and every mode is entered again once
+/// it is continued, before this returns.
+pub fn suspend() {
+    stop_all();
+    raise_default(SIGTSTP);
+    resume_all();
+}
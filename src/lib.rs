@@ -11,6 +11,11 @@
 //! For more information refer to the [README](https://github.com/ticki/termion).
 #![warn(missing_docs)]
 
+#[cfg(all(feature = "tokio", not(target_os = "redox")))]
+extern crate futures_core;
+#[cfg(all(feature = "tokio", not(target_os = "redox")))]
+extern crate tokio;
+
 #[cfg(target_os = "redox")]
 #[path="sys/redox/mod.rs"]
 mod sys;
@@ -25,33 +30,62 @@ pub use sys::tty::{is_tty, get_tty};
 mod async;
 pub use async::{AsyncReader, async_stdin};
 
+mod error;
+pub use error::Error;
+
 #[macro_use]
 mod macros;
+pub mod capabilities;
 pub mod clear;
 pub mod color;
 pub mod cursor;
 pub mod event;
+#[cfg(not(target_os = "redox"))]
+pub mod event_loop;
+pub mod identity;
 pub mod input;
+#[cfg(not(target_os = "redox"))]
+pub mod job_control;
+pub mod mode;
+pub mod query;
 pub mod raw;
+pub mod restore;
 pub mod screen;
 pub mod scroll;
+#[cfg(not(target_os = "redox"))]
+pub mod serial;
+#[cfg(all(feature = "tokio", not(target_os = "redox")))]
+pub mod stream;
 pub mod style;
+pub mod terminfo;
+pub mod termios;
 
 #[cfg(test)]
 mod test {
     use super::sys;
+    use std::io;
+    use std::os::unix::io::AsRawFd;
 
     #[test]
     fn test_get_terminal_attr() {
-        sys::attr::get_terminal_attr().unwrap();
-        sys::attr::get_terminal_attr().unwrap();
-        sys::attr::get_terminal_attr().unwrap();
+        let fd = io::stdin().as_raw_fd();
+        sys::attr::get_terminal_attr(fd).unwrap();
+        sys::attr::get_terminal_attr(fd).unwrap();
+        sys::attr::get_terminal_attr(fd).unwrap();
     }
 
     #[test]
     fn test_set_terminal_attr() {
-        let ios = sys::attr::get_terminal_attr().unwrap();
-        sys::attr::set_terminal_attr(&ios).unwrap();
+        let fd = io::stdin().as_raw_fd();
+        let ios = sys::attr::get_terminal_attr(fd).unwrap();
+        sys::attr::set_terminal_attr(fd, &ios).unwrap();
+    }
+
+    #[test]
+    fn test_terminal_attr_of_tty() {
+        let tty = sys::tty::get_tty().unwrap();
+        let ios = sys::attr::get_terminal_attr(tty.as_raw_fd()).unwrap();
+        sys::attr::set_terminal_attr(tty.as_raw_fd(), &ios).unwrap();
     }
 
     #[test]
@@ -0,0 +1,222 @@
+//! DEC private modes.
+//!
+//! Terminals ignore the modes they do not know, so setting one, such as with `MouseTerminal`, does
+//! not tell whether it works. `query_modes` asks the terminal (with DECRQM) whether it recognizes
+//! the modes, and whether they are set.
+//!
+//! The terminal must be in raw mode, so that the answers are not echoed, nor held back until the
+//! end of the line.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use termion::async_stdin;
+//! use termion::mode::{self, query_mode};
+//! use termion::query::DEFAULT_TIMEOUT;
+//! use termion::raw::IntoRawMode;
+//! use std::io::stdout;
+//!
+//! fn main() {
+//!     let mut stdout = stdout().into_raw_mode().unwrap();
+//!     let mut stdin = async_stdin();
+//!
+//!     let state = query_mode(&mut stdout, &mut stdin, mode::SYNCHRONIZED_OUTPUT, DEFAULT_TIMEOUT)
+//!         .unwrap();
+//!     println!("Synchronized output: {:?}\r", state);
+//! }
+//! ```
+
+use std::io::{self, Read, Write};
+use std::str;
+use std::time::Duration;
+
+use error::Error;
+use query::{find_sequence, query_many, Unread};
+
+/// Report mouse button presses (X11 mouse tracking).
+pub const MOUSE_X11: u16 = 1000;
+/// Report mouse motion while a button is pressed.
+pub const MOUSE_BUTTON_EVENT: u16 = 1002;
+/// Report all mouse motion.
+pub const MOUSE_ANY_EVENT: u16 = 1003;
+/// Report focus changes.
+pub const FOCUS_EVENT: u16 = 1004;
+/// Encode mouse reports in the SGR format.
+pub const MOUSE_SGR: u16 = 1006;
+/// Encode mouse reports in the urxvt format.
+pub const MOUSE_URXVT: u16 = 1015;
+/// The alternate screen, as used by `AlternateScreen`.
+pub const ALTERNATE_SCREEN: u16 = 1049;
+/// Bracketed paste.
+pub const BRACKETED_PASTE: u16 = 2004;
+/// Synchronized output, holding the display until the mode is reset.
+pub const SYNCHRONIZED_OUTPUT: u16 = 2026;
+/// Grapheme clustering, giving the width of whole grapheme clusters rather than code points.
+pub const GRAPHEME_CLUSTERING: u16 = 2027;
+
+/// The state of a mode, as reported by the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModeState {
+    /// The terminal does not know the mode.
+    NotRecognized,
+    /// The mode is set.
+    Set,
+    /// The mode is reset.
+    Reset,
+    /// The mode is set, and cannot be changed.
+    PermanentlySet,
+    /// The mode is reset, and cannot be changed.
+    PermanentlyReset,
+}
+
+impl ModeState {
+    /// Check whether the terminal knows the mode and allows setting it.
+    pub fn is_supported(self) -> bool {
+        match self {
+            ModeState::Set | ModeState::Reset | ModeState::PermanentlySet => true,
+            ModeState::NotRecognized | ModeState::PermanentlyReset => false,
+        }
+    }
+
+    /// Check whether the mode is set.
+    pub fn is_set(self) -> bool {
+        self == ModeState::Set || self == ModeState::PermanentlySet
+    }
+
+    fn from_code(code: u16) -> Option<ModeState> {
+        match code {
+            0 => Some(ModeState::NotRecognized),
+            1 => Some(ModeState::Set),
+            2 => Some(ModeState::Reset),
+            3 => Some(ModeState::PermanentlySet),
+            4 => Some(ModeState::PermanentlyReset),
+            _ => None,
+        }
+    }
+}
+
+/// Ask the terminal for the state of a private mode, reading the answer from `reader`.
+///
+/// See `query_modes`.
+pub fn query_mode<W, R>(writer: &mut W, reader: &mut R, mode: u16, timeout: Duration)
+                        -> io::Result<ModeState>
+    where W: Write + ?Sized,
+          R: Read + Unread + ?Sized
+{
+    query_modes(writer, reader, &[mode], timeout).map(|states| states[0])
+}
+
+/// Ask the terminal for the states of private modes, reading the answers from `reader`.
+///
+/// The states are given in the order of `modes`. The modes of a terminal which does not
+/// implement DECRQM are reported as `NotRecognized`, without waiting for the timeout, unless it
+/// does not answer the Primary Device Attributes either: this fails with `Error::QueryTimeout`.
+///
+/// The other input read meanwhile is put back into `reader`. See the `query` module.
+pub fn query_modes<W, R>(writer: &mut W, reader: &mut R, modes: &[u16], timeout: Duration)
+                         -> io::Result<Vec<ModeState>>
+    where W: Write + ?Sized,
+          R: Read + Unread + ?Sized
+{
+    // DECRQM is `CSI ? Pm $ p`, answered with `CSI ? Pm ; Ps $ y`.
+    // DA1 is asked last, as in `identity`, so that unanswered requests do not wait for the timeout.
+    let mut request = String::new();
+    for mode in modes {
+        request.push_str(&format!("\x1B[?{}$p", mode));
+    }
+    request.push_str("\x1B[c");
+
+    query_many(writer, reader, &request, timeout, |input| {
+        let is_param = |b: u8| b.is_ascii_digit() || b == b';';
+        let (da1, _) = find_sequence(input, b"\x1B[?", is_param, b"c")?;
+
+        let mut states = vec![ModeState::NotRecognized; modes.len()];
+        let mut ranges = Vec::new();
+        let mut from = 0;
+        while let Some((range, params)) =
+            find_sequence(&input[from..da1.start], b"\x1B[?", |b| is_param(b) || b == b'$', b"y") {
+            let range = from + range.start..from + range.end;
+            let params = &input[from + params.start..from + params.end];
+
+            match parse_report(params) {
+                Some((mode, state)) => {
+                    for (i, _) in modes.iter().enumerate().filter(|&(_, &m)| m == mode) {
+                        states[i] = state;
+                    }
+                }
+                None => {
+                    let err = Error::MalformedResponse { bytes: input[range.clone()].to_vec() };
+                    return Some((Err(err), vec![range, da1]));
+                }
+            }
+
+            from = range.end;
+            ranges.push(range);
+        }
+        ranges.push(da1);
+
+        Some((Ok(states), ranges))
+    })
+}
+
+/// Parse the `Pm ; Ps $` parameters of a mode report.
+fn parse_report(params: &[u8]) -> Option<(u16, ModeState)> {
+    let params = str::from_utf8(params).ok()?.strip_suffix('$')?;
+    let mut nums = params.split(';').map(|n| n.parse::<u16>().ok());
+
+    match (nums.next(), nums.next(), nums.next()) {
+        (Some(Some(mode)), Some(Some(state)), None) => Some((mode, ModeState::from_code(state)?)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use query::Pushback;
+
+    const TIMEOUT: Duration = Duration::from_millis(10);
+
+    #[test]
+    fn test_query_modes() {
+        let mut written = Vec::new();
+        let input = b"a\x1B[?2004;2$yb\x1B[?2026;0$y\x1B[?1006;1$y\x1B[?62;22cc";
+        let mut reader = Pushback::new(&input[..]);
+
+        let states = query_modes(&mut written,
+                                 &mut reader,
+                                 &[BRACKETED_PASTE, SYNCHRONIZED_OUTPUT, MOUSE_SGR, FOCUS_EVENT],
+                                 TIMEOUT)
+            .unwrap();
+        assert_eq!(states,
+                   [ModeState::Reset, ModeState::NotRecognized, ModeState::Set,
+                    ModeState::NotRecognized]);
+        assert_eq!(&written[..],
+                   &b"\x1B[?2004$p\x1B[?2026$p\x1B[?1006$p\x1B[?1004$p\x1B[c"[..]);
+
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, b"abc");
+
+        assert!(states[0].is_supported());
+        assert!(!states[0].is_set());
+        assert!(states[2].is_set());
+    }
+
+    #[test]
+    fn test_query_mode_errors() {
+        let mut reader = Pushback::new(&b"\x1B[?2004;7$y\x1B[?1;2c"[..]);
+        match Error::from(query_mode(&mut Vec::new(), &mut reader, BRACKETED_PASTE, TIMEOUT)
+                              .unwrap_err()) {
+            Error::MalformedResponse { bytes } => assert_eq!(bytes, b"\x1B[?2004;7$y"),
+            err => panic!("unexpected error: {:?}", err),
+        }
+
+        let mut reader = Pushback::new(&b""[..]);
+        match Error::from(query_mode(&mut Vec::new(), &mut reader, BRACKETED_PASTE, TIMEOUT)
+                              .unwrap_err()) {
+            Error::QueryTimeout { .. } => {}
+            err => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
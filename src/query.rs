@@ -0,0 +1,274 @@
+//! Querying the terminal.
+//!
+//! Some information can only be obtained by asking the terminal: a request is written out, and
+//! the terminal answers on the input, mixed with whatever the user is typing. `query` sends a
+//! request, reads until the answer is found or the timeout expires, and gives back the other bytes
+//! to the reader, so no keystroke is lost.
+//!
+//! The reader must not block, such as the one returned by `async_stdin`, which can take bytes back
+//! through the `Unread` trait. Other non-blocking readers can be wrapped in a `Pushback`.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use termion::async_stdin;
+//! use termion::cursor;
+//! use termion::query::DEFAULT_TIMEOUT;
+//! use termion::raw::IntoRawMode;
+//! use std::io::{Read, stdout};
+//!
+//! fn main() {
+//!     let mut stdout = stdout().into_raw_mode().unwrap();
+//!     let mut stdin = async_stdin();
+//!
+//!     let pos = cursor::query_cursor_pos(&mut stdout, &mut stdin, DEFAULT_TIMEOUT).unwrap();
+//!
+//!     // The keys typed while waiting for the answer are still there.
+//!     let mut keys = Vec::new();
+//!     stdin.read_to_end(&mut keys).unwrap();
+//! }
+//! ```
+
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::ops::Range;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use error::Error;
+use raw::CONTROL_SEQUENCE_TIMEOUT;
+
+/// The time to wait for an answer, unless told otherwise.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_millis(CONTROL_SEQUENCE_TIMEOUT);
+
+/// How long to wait before reading again when nothing was available.
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Readers which can take back bytes, to be read again before anything else.
+pub trait Unread {
+    /// Put the bytes back in front of the input.
+    fn unread(&mut self, bytes: &[u8]);
+}
+
+/// A reader which can take back bytes, wrapping any other reader.
+pub struct Pushback<R> {
+    inner: R,
+    unread: VecDeque<u8>,
+}
+
+impl<R: Read> Pushback<R> {
+    /// Wrap a reader.
+    pub fn new(inner: R) -> Pushback<R> {
+        Pushback {
+            inner,
+            unread: VecDeque::new(),
+        }
+    }
+
+    /// Get the inner reader back, losing the bytes which were put back.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for Pushback<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.unread.is_empty() {
+            return self.inner.read(buf);
+        }
+        self.unread.read(buf)
+    }
+}
+
+impl<R> Unread for Pushback<R> {
+    fn unread(&mut self, bytes: &[u8]) {
+        for &byte in bytes.iter().rev() {
+            self.unread.push_front(byte);
+        }
+    }
+}
+
+/// The result of parsing the input read so far.
+pub type Parsed<T> = Option<(Result<T, Error>, Range<usize>)>;
+
+/// Send a request to the terminal and wait for its answer.
+///
+/// `parse` is given everything read so far, every time more is available. Once it finds the
+/// answer, it returns the parsed value, or the error if it is malformed, along with where the
+/// answer lies in the input. Until then, it returns `None`.
+///
+/// The input surrounding the answer is put back into the reader. If there is no answer in time,
+/// this fails with `Error::QueryTimeout`, and all the input is put back.
+pub fn query<W, R, T, P>(writer: &mut W,
+                         reader: &mut R,
+                         request: &str,
+                         timeout: Duration,
+                         mut parse: P)
+                         -> io::Result<T>
+    where W: Write + ?Sized,
+          R: Read + Unread + ?Sized,
+          P: FnMut(&[u8]) -> Parsed<T>
+{
+    query_many(writer, reader, request, timeout, |input| {
+        parse(input).map(|(res, range)| (res, vec![range]))
+    })
+}
+
+/// Send requests to the terminal and wait for their answers, like `query`.
+///
+/// The answers are found in the given ranges, which must be in order and must not overlap.
+pub(crate) fn query_many<W, R, T, P>(writer: &mut W,
+                                     reader: &mut R,
+                                     request: &str,
+                                     timeout: Duration,
+                                     mut parse: P)
+                                     -> io::Result<T>
+    where W: Write + ?Sized,
+          R: Read + Unread + ?Sized,
+          P: FnMut(&[u8]) -> Option<(Result<T, Error>, Vec<Range<usize>>)>
+{
+    writer.write_all(request.as_bytes())?;
+    writer.flush()?;
+
+    let start = Instant::now();
+    let mut input = Vec::new();
+    let mut buf = [0; 64];
+
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(n) => n,
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => 0,
+            Err(e) => {
+                reader.unread(&input);
+                return Err(e);
+            }
+        };
+
+        if n > 0 {
+            input.extend_from_slice(&buf[..n]);
+            if let Some((res, ranges)) = parse(&input) {
+                let mut rest = Vec::new();
+                let mut from = 0;
+                for range in ranges {
+                    rest.extend_from_slice(&input[from..range.start]);
+                    from = range.end;
+                }
+                rest.extend_from_slice(&input[from..]);
+                reader.unread(&rest);
+                return res.map_err(Into::into);
+            }
+        } else if start.elapsed() >= timeout {
+            reader.unread(&input);
+            return Err(Error::QueryTimeout { query: format!("{:?}", request) }.into());
+        } else {
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+/// Find the first control sequence in `input` starting with `intro`, with parameters made of the
+/// bytes accepted by `param`, and ended by one of the bytes of `end`.
+///
+/// This returns the range of the sequence, and the range of its parameters.
+pub(crate) fn find_sequence<F>(input: &[u8],
+                               intro: &[u8],
+                               param: F,
+                               end: &[u8])
+                               -> Option<(Range<usize>, Range<usize>)>
+    where F: Fn(u8) -> bool
+{
+    let mut from = 0;
+    while let Some(pos) = input[from..].windows(intro.len()).position(|w| w == intro) {
+        let start = from + pos;
+        let params = start + intro.len();
+        let len = input[params..].iter().take_while(|&&b| param(b)).count();
+
+        match input.get(params + len) {
+            Some(b) if end.contains(b) => {
+                return Some((start..params + len + 1, params..params + len));
+            }
+            _ => from = start + 1,
+        }
+    }
+    None
+}
+
+/// Find the first string sequence in `input` starting with `intro`, such as an OSC answer, and
+/// ended by BEL or ST.
+///
+/// This returns the range of the sequence, and the range of its content.
+pub(crate) fn find_string(input: &[u8], intro: &[u8]) -> Option<(Range<usize>, Range<usize>)> {
+    let start = input.windows(intro.len()).position(|w| w == intro)?;
+    let content = start + intro.len();
+    let len = input[content..].iter().position(|&b| b == b'\x07' || b == b'\x1B')?;
+
+    let end = content + len;
+    match (input[end], input.get(end + 1)) {
+        (b'\x07', _) => Some((start..end + 1, content..end)),
+        (_, Some(&b'\\')) => Some((start..end + 2, content..end)),
+        // The rest of ST has not been read yet.
+        (_, None) => None,
+        (_, Some(_)) => Some((start..end, content..end)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pushback() {
+        let mut reader = Pushback::new(&b"cd"[..]);
+        reader.unread(b"ab");
+
+        let mut buf = String::new();
+        reader.read_to_string(&mut buf).unwrap();
+        assert_eq!(buf, "abcd");
+    }
+
+    #[test]
+    fn test_query() {
+        let mut written = Vec::new();
+        let mut reader = Pushback::new(&b"ab\x1B[42Xcd"[..]);
+
+        let res = query(&mut written, &mut reader, "\x1B[?", DEFAULT_TIMEOUT, |input| {
+            find_sequence(input, b"\x1B[", |b| b.is_ascii_digit(), b"X")
+                .map(|(range, params)| (Ok(input[params].to_vec()), range))
+        });
+        assert_eq!(res.unwrap(), b"42");
+        assert_eq!(written, b"\x1B[?");
+
+        let mut rest = String::new();
+        reader.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "abcd");
+    }
+
+    #[test]
+    fn test_find_string() {
+        let input = b"a\x1B]4;1;rgb:cd/00/00\x07b";
+        assert_eq!(find_string(input, b"\x1B]4;"), Some((1..20, 5..19)));
+
+        let input = b"\x1B]11;rgb:0/0/0\x1B\\";
+        assert_eq!(find_string(input, b"\x1B]11;"), Some((0..16, 5..14)));
+        assert_eq!(find_string(&input[..15], b"\x1B]11;"), None);
+    }
+
+    #[test]
+    fn test_query_timeout() {
+        let mut reader = Pushback::new(&b"abc"[..]);
+
+        let res = query(&mut Vec::new(),
+                        &mut reader,
+                        "\x1B[?",
+                        Duration::from_millis(10),
+                        |_| None::<(Result<(), Error>, Range<usize>)>);
+        match Error::from(res.unwrap_err()) {
+            Error::QueryTimeout { .. } => {}
+            err => panic!("unexpected error: {:?}", err),
+        }
+
+        let mut rest = String::new();
+        reader.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "abc");
+    }
+}
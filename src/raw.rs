@@ -9,6 +9,12 @@
 //!
 //! It is essential to design terminal programs.
 //!
+//! For programs which only need some of these, such as keeping ctrl-c working while reading
+//! single key presses, `ModeBuilder` sets up the modes in between cooked and raw mode.
+//!
+//! Raw mode is set on the terminal the writer refers to, through its file descriptor, so it works
+//! on any TTY and not only on the one connected to the standard streams.
+//!
 //! # Example
 //!
 //! ```rust,no_run
@@ -24,9 +30,11 @@
 
 use std::io::{self, Write};
 use std::ops;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::time::Duration;
 
-use sys::Termios;
-use sys::attr::{get_terminal_attr, raw_terminal_attr, set_terminal_attr};
+use restore::{self, Mode};
+use termios::{ControlChar, InputFlags, LocalFlags, OutputFlags, Termios};
 
 /// The timeout of an escape code control sequence, in milliseconds.
 pub const CONTROL_SEQUENCE_TIMEOUT: u64 = 100;
@@ -34,15 +42,100 @@ pub const CONTROL_SEQUENCE_TIMEOUT: u64 = 100;
 /// A terminal restorer, which keeps the previous state of the terminal, and restores it, when
 /// dropped.
 ///
-/// Restoring will entirely bring back the old TTY state.
+/// Restoring will entirely bring back the old TTY state. This is returned both for raw mode and
+/// for the modes set up with `ModeBuilder`.
 pub struct RawTerminal<W: Write> {
     prev_ios: Termios,
-    output: W,
+    ios: Termios,
+    fd: RawFd,
+    id: usize,
+    /// The output, taken by `restore`.
+    output: Option<W>,
+}
+
+impl<W: Write> RawTerminal<W> {
+    /// Switch the terminal behind `fd` to raw mode, and wrap the output in a restorer.
+    pub(crate) fn new(output: W, fd: RawFd) -> io::Result<RawTerminal<W>> {
+        RawTerminal::with_attr(output, fd, Termios::make_raw)
+    }
+
+    /// Change the attributes of the terminal behind `fd` with `f`, and wrap the output in a
+    /// restorer.
+    fn with_attr<F>(output: W, fd: RawFd, f: F) -> io::Result<RawTerminal<W>>
+        where F: FnOnce(&mut Termios)
+    {
+        let mut ios = Termios::get(fd)?;
+        let prev_ios = ios;
+
+        f(&mut ios);
+
+        ios.set(fd)?;
+
+        Ok(RawTerminal {
+            prev_ios,
+            ios,
+            fd,
+            id: restore::register(Mode::Raw { fd, prev_ios, ios }),
+            output: Some(output),
+        })
+    }
+
+    /// Temporarily switch the terminal back to the mode it had before raw mode was entered.
+    pub fn suspend_raw_mode(&self) -> io::Result<()> {
+        self.prev_ios.set(self.fd)?;
+        restore::set_active(self.id, false);
+        Ok(())
+    }
+
+    /// Switch the terminal back to raw mode after `suspend_raw_mode`.
+    pub fn activate_raw_mode(&self) -> io::Result<()> {
+        self.ios.set(self.fd)?;
+        restore::set_active(self.id, true);
+        Ok(())
+    }
+
+    /// Run a closure with raw mode suspended, for example to hand the terminal to a child process.
+    ///
+    /// The closure is given the inner writer, so that the wrappers it may hold can be suspended
+    /// too. Raw mode is activated again once the closure returns.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use termion::raw::IntoRawMode;
+    /// use std::io::stdout;
+    /// use std::process::Command;
+    ///
+    /// let mut stdout = stdout().into_raw_mode().unwrap();
+    /// let status = stdout.suspended(|_| Command::new("less").arg("README.md").status()).unwrap();
+    /// ```
+    pub fn suspended<F, T>(&mut self, f: F) -> io::Result<T>
+        where F: FnOnce(&mut W) -> T
+    {
+        self.suspend_raw_mode()?;
+        let res = f(&mut **self);
+        self.activate_raw_mode()?;
+        Ok(res)
+    }
+
+    /// Restore the previous mode of the terminal, and get the inner writer back.
+    ///
+    /// Unlike dropping the wrapper, this reports failures to restore the terminal, in which case
+    /// the writer is dropped.
+    pub fn restore(mut self) -> io::Result<W> {
+        restore::unregister(self.id);
+        let output = self.output.take().expect("the output is only taken when restoring");
+        self.prev_ios.set(self.fd).map(|_| output)
+    }
 }
 
 impl<W: Write> Drop for RawTerminal<W> {
     fn drop(&mut self) {
-        set_terminal_attr(&self.prev_ios).unwrap();
+        // Errors cannot be reported from here; `restore` does that.
+        if self.output.is_some() {
+            let _ = self.prev_ios.set(self.fd);
+            restore::unregister(self.id);
+        }
     }
 }
 
@@ -50,23 +143,29 @@ impl<W: Write> ops::Deref for RawTerminal<W> {
     type Target = W;
 
     fn deref(&self) -> &W {
-        &self.output
+        self.output.as_ref().expect("the output is only taken when restoring")
     }
 }
 
 impl<W: Write> ops::DerefMut for RawTerminal<W> {
     fn deref_mut(&mut self) -> &mut W {
-        &mut self.output
+        self.output.as_mut().expect("the output is only taken when restoring")
     }
 }
 
 impl<W: Write> Write for RawTerminal<W> {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.output.write(buf)
+        (**self).write(buf)
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        self.output.flush()
+        (**self).flush()
+    }
+}
+
+impl<W: Write + AsRawFd> AsRawFd for RawTerminal<W> {
+    fn as_raw_fd(&self) -> RawFd {
+        (**self).as_raw_fd()
     }
 }
 
@@ -76,28 +175,164 @@ impl<W: Write> Write for RawTerminal<W> {
 ///
 /// TTYs has their state controlled by the writer, not the reader. You use the writer to clear the
 /// screen, move the cursor and so on, so naturally you use the writer to change the mode as well.
-pub trait IntoRawMode: Write + Sized {
+pub trait IntoRawMode: Write + AsRawFd + Sized {
     /// Switch to raw mode.
     ///
     /// Raw mode means that stdin won't be printed (it will instead have to be written manually by
     /// the program). Furthermore, the input isn't canonicalised or buffered (that is, you can
     /// read from stdin one byte of a time). The output is neither modified in any way.
+    ///
+    /// The mode is changed on the terminal behind this writer's file descriptor.
     fn into_raw_mode(self) -> io::Result<RawTerminal<Self>>;
 }
 
-impl<W: Write> IntoRawMode for W {
+impl<W: Write + AsRawFd> IntoRawMode for W {
     fn into_raw_mode(self) -> io::Result<RawTerminal<W>> {
-        let mut ios = get_terminal_attr()?;
-        let prev_ios = ios;
+        let fd = self.as_raw_fd();
+        RawTerminal::new(self, fd)
+    }
+}
 
-        raw_terminal_attr(&mut ios);
+/// A builder for the modes in between cooked and raw mode.
+///
+/// Only the settings which are given are changed; the terminal keeps its current value for the
+/// others. The previous attributes are restored when the returned `RawTerminal` is dropped.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use termion::raw::ModeBuilder;
+/// use std::io::stdout;
+/// use std::time::Duration;
+///
+/// // Read keys one by one without echoing them, while ctrl-c still interrupts the program.
+/// let stdout = ModeBuilder::cbreak().apply(stdout()).unwrap();
+///
+/// // Return from reads after 100 milliseconds even if nothing was typed.
+/// let stdout = ModeBuilder::cbreak()
+///     .read_timeout(0, Duration::from_millis(100))
+///     .apply(stdout)
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ModeBuilder {
+    canonical: Option<bool>,
+    echo: Option<bool>,
+    signals: Option<bool>,
+    output_processing: Option<bool>,
+    flow_control: Option<bool>,
+    read_timeout: Option<(u8, u8)>,
+}
 
-        set_terminal_attr(&ios)?;
+impl ModeBuilder {
+    /// Create a builder leaving every setting as it is.
+    pub fn new() -> ModeBuilder {
+        ModeBuilder::default()
+    }
 
-        Ok(RawTerminal {
-            prev_ios: prev_ios,
-            output: self,
-        })
+    /// Create a builder for cbreak mode.
+    ///
+    /// The input is given byte-by-byte and not echoed, but ctrl-c and ctrl-z still send their
+    /// signals, and the output is still post-processed, so `\n` goes to the start of the next
+    /// line.
+    pub fn cbreak() -> ModeBuilder {
+        ModeBuilder::new()
+            .canonical(false)
+            .echo(false)
+            .signals(true)
+            .output_processing(true)
+    }
+
+    /// Set whether the input is line buffered, and can be edited before being given to the
+    /// program.
+    ///
+    /// When turned off without a `read_timeout`, reads block until at least one byte is available.
+    pub fn canonical(mut self, enable: bool) -> ModeBuilder {
+        self.canonical = Some(enable);
+        self
+    }
+
+    /// Set whether the input is written out by the terminal.
+    pub fn echo(mut self, enable: bool) -> ModeBuilder {
+        self.echo = Some(enable);
+        self
+    }
+
+    /// Set whether ctrl-c, ctrl-z and `ctrl-\` send `SIGINT`, `SIGTSTP` and `SIGQUIT`, rather
+    /// than being read as input.
+    pub fn signals(mut self, enable: bool) -> ModeBuilder {
+        self.signals = Some(enable);
+        self
+    }
+
+    /// Set whether the output is post-processed, which most notably translates `\n` to `\r\n`.
+    pub fn output_processing(mut self, enable: bool) -> ModeBuilder {
+        self.output_processing = Some(enable);
+        self
+    }
+
+    /// Set whether ctrl-s and ctrl-q pause and resume the output (XON/XOFF flow control), rather
+    /// than being read as input.
+    pub fn flow_control(mut self, enable: bool) -> ModeBuilder {
+        self.flow_control = Some(enable);
+        self
+    }
+
+    /// Set when reads return, as the `VMIN` and `VTIME` settings do.
+    ///
+    /// A read returns once `min` bytes are available, or once `time` has passed without input,
+    /// whichever comes first. With a `min` of 0, `time` is counted from the start of the read,
+    /// and it returns nothing when it expires; with a zero `time`, it is not used at all. The time
+    /// is rounded down to tenths of a second, up to 25.5 seconds.
+    ///
+    /// This only has an effect when the input is not canonical.
+    pub fn read_timeout(mut self, min: u8, time: Duration) -> ModeBuilder {
+        let tenths = time.as_secs() * 10 + u64::from(time.subsec_nanos() / 100_000_000);
+        self.read_timeout = Some((min, tenths.min(u64::from(u8::MAX)) as u8));
+        self
+    }
+
+    /// Set up the mode on the terminal behind this writer's file descriptor.
+    pub fn apply<W: Write + AsRawFd>(&self, output: W) -> io::Result<RawTerminal<W>> {
+        let fd = output.as_raw_fd();
+        RawTerminal::with_attr(output, fd, |ios| self.apply_attr(ios))
+    }
+
+    fn apply_attr(&self, ios: &mut Termios) {
+        let mut local = ios.local_flags();
+        if let Some(enable) = self.canonical {
+            local.set(LocalFlags::ICANON, enable);
+        }
+        if let Some(enable) = self.echo {
+            local.set(LocalFlags::ECHO, enable);
+        }
+        if let Some(enable) = self.signals {
+            local.set(LocalFlags::ISIG, enable);
+        }
+        ios.set_local_flags(local);
+
+        if let Some(enable) = self.output_processing {
+            let mut output = ios.output_flags();
+            output.set(OutputFlags::OPOST, enable);
+            ios.set_output_flags(output);
+        }
+
+        if let Some(enable) = self.flow_control {
+            let mut input = ios.input_flags();
+            input.set(InputFlags::IXON | InputFlags::IXOFF, enable);
+            ios.set_input_flags(input);
+        }
+
+        let read_timeout = match (self.read_timeout, self.canonical) {
+            (Some(read_timeout), _) => Some(read_timeout),
+            // Block until there is input, rather than leaving over whatever was set before.
+            (None, Some(false)) => Some((1, 0)),
+            (None, _) => None,
+        };
+        if let Some((min, time)) = read_timeout {
+            ios.set_control_char(ControlChar::Min, min);
+            ios.set_control_char(ControlChar::Time, time);
+        }
     }
 }
 
@@ -114,4 +349,74 @@ mod test {
 
         drop(out);
     }
+
+    #[test]
+    fn test_not_a_tty() {
+        use std::fs::OpenOptions;
+        use error::Error;
+
+        let null = OpenOptions::new().write(true).open("/dev/null").unwrap();
+        match null.into_raw_mode().map_err(Error::from) {
+            Err(Error::NotATty) => {}
+            Err(e) => panic!("unexpected error: {:?}", e),
+            Ok(_) => panic!("/dev/null is not a terminal"),
+        }
+    }
+
+    #[test]
+    fn test_restore() {
+        let out = stdout().into_raw_mode().unwrap();
+        let mut out = out.restore().unwrap();
+
+        out.write_all(b"this is cooked after raw mode\n").unwrap();
+    }
+
+    #[test]
+    fn test_suspend_raw_mode() {
+        let mut out = stdout().into_raw_mode().unwrap();
+
+        out.suspend_raw_mode().unwrap();
+        out.write_all(b"this is cooked\n").unwrap();
+        out.activate_raw_mode().unwrap();
+
+        let res = out.suspended(|out| out.write_all(b"this is cooked again\n")).unwrap();
+        res.unwrap();
+    }
+
+    #[test]
+    fn test_mode_builder() {
+        let mut ios = Termios::get(stdout().as_raw_fd()).unwrap();
+        let mut local = ios.local_flags();
+        local.remove(LocalFlags::ISIG);
+        local.insert(LocalFlags::ICANON | LocalFlags::ECHO);
+        ios.set_local_flags(local);
+        ios.set_input_flags(ios.input_flags() | InputFlags::IXON);
+
+        let echo = LocalFlags::ICANON | LocalFlags::ECHO | LocalFlags::ISIG;
+        ModeBuilder::cbreak().flow_control(false).apply_attr(&mut ios);
+        assert_eq!(ios.local_flags() & echo, LocalFlags::ISIG);
+        assert!(ios.output_flags().contains(OutputFlags::OPOST));
+        assert!(!ios.input_flags().intersects(InputFlags::IXON | InputFlags::IXOFF));
+        assert_eq!(ios.control_char(ControlChar::Min), 1);
+        assert_eq!(ios.control_char(ControlChar::Time), 0);
+
+        ModeBuilder::new().read_timeout(0, Duration::from_millis(2500)).apply_attr(&mut ios);
+        assert_eq!(ios.local_flags() & echo, LocalFlags::ISIG);
+        assert_eq!(ios.control_char(ControlChar::Min), 0);
+        assert_eq!(ios.control_char(ControlChar::Time), 25);
+
+        let mut out = ModeBuilder::cbreak().apply(stdout()).unwrap();
+        out.write_all(b"this is a test in cbreak mode\n").unwrap();
+    }
+
+    #[test]
+    fn test_into_raw_mode_on_tty() {
+        use sys::tty::get_tty;
+
+        let mut out = get_tty().unwrap().into_raw_mode().unwrap();
+
+        out.write_all(b"this is a test on the tty\r\n").unwrap();
+
+        drop(out);
+    }
 }
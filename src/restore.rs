@@ -0,0 +1,336 @@
+//! Restoring the terminal when the program panics or is killed.
+//!
+//! `RawTerminal`, `AlternateScreen` and `MouseTerminal` undo their changes to the terminal when
+//! dropped, but that is too late for a panic message, which ends up on the alternate screen, and
+//! it never happens when the program is terminated by a signal. To handle those cases, every
+//! active mode is recorded in a global registry, which the hooks installed by this module use to
+//! bring the terminal back to its original state.
+//!
+//! The hooks are opt-in.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use termion::raw::IntoRawMode;
+//! use termion::restore;
+//! use termion::screen::AlternateScreen;
+//! use std::io::stdout;
+//!
+//! fn main() {
+//!     restore::install_panic_hook();
+//!     restore::install_signal_handlers().unwrap();
+//!
+//!     let screen = AlternateScreen::new(stdout().into_raw_mode().unwrap()).unwrap();
+//!     panic!("This message is printed on the main screen.");
+//! }
+//! ```
+
+use std::fs;
+use std::io::Write;
+use std::mem::ManuallyDrop;
+use std::os::unix::io::{FromRawFd, RawFd};
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, Once};
+
+use termios::Termios;
+
+/// A change made to the terminal, and how to undo and redo it.
+#[derive(Clone, Copy)]
+pub(crate) enum Mode {
+    /// Raw mode, set on the given file descriptor.
+    Raw {
+        fd: RawFd,
+        prev_ios: Termios,
+        ios: Termios,
+    },
+    /// A mode entered and left by writing escape sequences to the given file descriptor, or to
+    /// the fallback of `output_fd` if it is unknown.
+    Escape {
+        fd: Option<RawFd>,
+        enter: &'static str,
+        exit: &'static str,
+    },
+}
+
+struct Entry {
+    id: usize,
+    mode: Mode,
+    /// Whether the mode is in effect.
+    active: bool,
+    /// Whether the mode was left because the program is being stopped.
+    stopped: bool,
+}
+
+impl Entry {
+    fn leave(&mut self, out: RawFd) {
+        match self.mode {
+            Mode::Raw { fd, ref prev_ios, .. } => {
+                let _ = prev_ios.set(fd);
+            }
+            Mode::Escape { fd, exit, .. } => write_fd(fd.unwrap_or(out), exit.as_bytes()),
+        }
+        self.active = false;
+    }
+
+    fn enter(&mut self, out: RawFd) {
+        match self.mode {
+            Mode::Raw { fd, ref ios, .. } => {
+                let _ = ios.set(fd);
+            }
+            Mode::Escape { fd, enter, .. } => write_fd(fd.unwrap_or(out), enter.as_bytes()),
+        }
+        self.active = true;
+    }
+}
+
+static REGISTRY: Mutex<Vec<Entry>> = Mutex::new(Vec::new());
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+fn registry() -> MutexGuard<'static, Vec<Entry>> {
+    // The registry is never left inconsistent, so a panic while it was locked does not matter.
+    REGISTRY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Record an active mode, returning the identifier to update it with.
+pub(crate) fn register(mode: Mode) -> usize {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    registry().push(Entry {
+        id,
+        mode,
+        active: true,
+        stopped: false,
+    });
+    id
+}
+
+/// Forget about a mode, once it has been restored by its owner.
+pub(crate) fn unregister(id: usize) {
+    registry().retain(|entry| entry.id != id);
+}
+
+/// Record whether a mode is currently in effect.
+pub(crate) fn set_active(id: usize, active: bool) {
+    if let Some(entry) = registry().iter_mut().find(|entry| entry.id == id) {
+        entry.active = active;
+        entry.stopped = false;
+    }
+}
+
+/// Write to a file descriptor we do not own.
+fn write_fd(fd: RawFd, bytes: &[u8]) {
+    let mut file = ManuallyDrop::new(unsafe { fs::File::from_raw_fd(fd) });
+    let _ = file.write_all(bytes).and_then(|_| file.flush());
+}
+
+/// The file descriptor escape sequences of unknown output are written to: the one of the most
+/// recently entered raw mode, or the standard output.
+fn output_fd(registry: &[Entry]) -> RawFd {
+    registry.iter()
+        .rev()
+        .filter_map(|entry| match entry.mode {
+            Mode::Raw { fd, .. } => Some(fd),
+            Mode::Escape { .. } => None,
+        })
+        .next()
+        .unwrap_or(1)
+}
+
+/// Restore the terminal to the state it had before any of the active modes were entered.
+///
+/// Modes are undone in the reverse order of their creation. Escape sequences are written to the
+/// output of the wrapper which entered them when it is known, and otherwise to the terminal of
+/// the most recently entered raw mode, or to the standard output if there is none.
+///
+/// Restored modes are considered inactive afterwards, so calling this more than once is harmless.
+/// The wrappers still restore their mode when dropped.
+pub fn restore_all() {
+    restore_entries(&mut registry());
+}
+
+/// Undo the active modes of `entries`, as `restore_all` does.
+fn restore_entries(entries: &mut [Entry]) {
+    let out = output_fd(entries);
+
+    for entry in entries.iter_mut().rev().filter(|entry| entry.active) {
+        entry.leave(out);
+        entry.stopped = false;
+    }
+}
+
+/// Leave every active mode before the program is stopped, remembering them for `resume_all`.
+pub(crate) fn stop_all() {
+    stop_entries(&mut registry());
+}
+
+/// Leave the active modes of `entries`, as `stop_all` does.
+fn stop_entries(entries: &mut [Entry]) {
+    let out = output_fd(entries);
+
+    for entry in entries.iter_mut().rev().filter(|entry| entry.active) {
+        entry.leave(out);
+        entry.stopped = true;
+    }
+}
+
+/// Enter again the modes left by `stop_all`, after the program is continued.
+///
+/// Active raw modes are set again too, in case the shell changed the terminal attributes while
+/// the program was stopped by a signal which could not be caught.
+pub(crate) fn resume_all() {
+    resume_entries(&mut registry());
+}
+
+/// Enter again the modes of `entries` left by `stop_entries`, as `resume_all` does.
+fn resume_entries(entries: &mut [Entry]) {
+    let out = output_fd(entries);
+
+    for entry in entries.iter_mut() {
+        let raw = match entry.mode {
+            Mode::Raw { .. } => true,
+            Mode::Escape { .. } => false,
+        };
+        if entry.stopped || (entry.active && raw) {
+            entry.enter(out);
+            entry.stopped = false;
+        }
+    }
+}
+
+/// Install a panic hook restoring the terminal before the panic message is printed.
+///
+/// The previously installed hook is called afterwards, so the message is printed as usual. This
+/// only has an effect the first time it is called.
+pub fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        let prev_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            restore_all();
+            prev_hook(info);
+        }));
+    });
+}
+
+#[cfg(not(target_os = "redox"))]
+pub use self::signals::install_signal_handlers;
+
+#[cfg(not(target_os = "redox"))]
+mod signals {
+    use std::io;
+    use std::sync::Mutex;
+    use std::thread;
+
+    use sys::poll::poll;
+    use sys::signal::{raise_default, SignalPipe, SIGHUP, SIGINT, SIGQUIT, SIGTERM};
+
+    use super::restore_all;
+
+    /// Restore the terminal when the program is terminated by `SIGINT`, `SIGTERM`, `SIGHUP` or
+    /// `SIGQUIT`.
+    ///
+    /// The signals are handled on a background thread, which restores the terminal and then
+    /// terminates the program with the same signal, as if it had not been caught. This only has
+    /// an effect the first time it is called.
+    ///
+    /// Note that in raw mode, typing ctrl-c does not send `SIGINT`, but a `Key::Ctrl('c')` event.
+    pub fn install_signal_handlers() -> io::Result<()> {
+        static INSTALLED: Mutex<bool> = Mutex::new(false);
+
+        let mut installed = INSTALLED.lock().unwrap_or_else(|e| e.into_inner());
+        if *installed {
+            return Ok(());
+        }
+
+        let pipe = SignalPipe::new(&[SIGINT, SIGTERM, SIGHUP, SIGQUIT])?;
+        thread::Builder::new()
+            .name("termion-restore".to_owned())
+            .spawn(move || loop {
+                if poll(&[pipe.fd()], None).is_err() {
+                    return;
+                }
+                if let Some(&signal) = pipe.drain().first() {
+                    restore_all();
+                    raise_default(signal);
+                }
+            })?;
+
+        *installed = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::os::unix::io::AsRawFd;
+    use sys::tty::{open_pty, open_tty};
+    use termios::LocalFlags;
+
+    /// An active entry, outside of the registry, so that the tests do not touch the modes of
+    /// each other.
+    fn entry(mode: Mode) -> Entry {
+        Entry {
+            id: 0,
+            mode,
+            active: true,
+            stopped: false,
+        }
+    }
+
+    #[test]
+    fn test_registry() {
+        let screen = register(Mode::Escape { fd: None, enter: "", exit: "" });
+        assert!(registry().iter().any(|entry| entry.id == screen && entry.active));
+        set_active(screen, false);
+        assert!(registry().iter().any(|entry| entry.id == screen && !entry.active));
+
+        unregister(screen);
+        assert!(registry().iter().all(|entry| entry.id != screen));
+    }
+
+    #[test]
+    fn test_stop_and_resume() {
+        let (_master, path) = open_pty().unwrap();
+        let tty = open_tty(&path).unwrap();
+        let fd = tty.as_raw_fd();
+        let prev_ios = Termios::get(fd).unwrap();
+        let mut ios = prev_ios;
+        ios.make_raw();
+        ios.set(fd).unwrap();
+        let canonical = || Termios::get(fd).unwrap().local_flags().contains(LocalFlags::ICANON);
+
+        let mut entries = vec![entry(Mode::Raw { fd, prev_ios, ios }),
+                               entry(Mode::Escape { fd: Some(fd), enter: "", exit: "" })];
+        entries[1].active = false;
+
+        stop_entries(&mut entries);
+        assert!(canonical());
+        assert!(!entries[0].active && entries[0].stopped);
+        assert!(!entries[1].stopped);
+
+        resume_entries(&mut entries);
+        assert!(!canonical());
+        assert!(entries[0].active && !entries[0].stopped);
+        assert!(!entries[1].active);
+
+        restore_entries(&mut entries);
+        assert!(canonical());
+        assert!(!entries[0].active);
+    }
+
+    #[test]
+    fn test_escape_output() {
+        let path = std::env::temp_dir().join(format!("termion-restore-{}", std::process::id()));
+        let file = fs::File::create(&path).unwrap();
+
+        let fd = Some(file.as_raw_fd());
+        let mut entries = [entry(Mode::Escape { fd, enter: "in", exit: "out" })];
+        restore_entries(&mut entries);
+        restore_entries(&mut entries);
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "out");
+        fs::remove_file(&path).unwrap();
+    }
+}
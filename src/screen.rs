@@ -22,13 +22,22 @@
 use std::io::{self, Write};
 use std::ops;
 use std::fmt;
+use std::os::unix::io::{AsRawFd, RawFd};
+
+use restore::{self, Mode};
+
+/// The escape code switching to the alternate screen.
+const ALTERNATE_SCREEN_SEQUENCE: &str = csi!("?1049h");
+
+/// The escape code switching to the main screen.
+const MAIN_SCREEN_SEQUENCE: &str = csi!("?1049l");
 
 /// Switch to the main screen buffer of the terminal.
 pub struct ToMainScreen;
 
 impl fmt::Display for ToMainScreen {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, csi!("?1049l"))
+        f.write_str(MAIN_SCREEN_SEQUENCE)
     }
 }
 
@@ -37,7 +46,7 @@ pub struct ToAlternateScreen;
 
 impl fmt::Display for ToAlternateScreen {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, csi!("?1049h"))
+        f.write_str(ALTERNATE_SCREEN_SEQUENCE)
     }
 }
 
@@ -47,22 +56,109 @@ impl fmt::Display for ToAlternateScreen {
 /// This is achieved by switching the terminal to the alternate screen on creation and
 /// automatically switching it back to the original screen on drop.
 pub struct AlternateScreen<W: Write> {
-    /// The output target.
-    output: W,
+    /// The output target, taken by `restore`.
+    output: Option<W>,
+    /// The identifier of the screen in the restore registry.
+    id: usize,
 }
 
 impl<W: Write> AlternateScreen<W> {
     /// Create an alternate screen wrapper struct for the provided output and switch the terminal
     /// to the alternate screen.
-    pub fn from(mut output: W) -> Self {
-        write!(output, "{}", ToAlternateScreen).expect("switch to alternate screen");
-        AlternateScreen { output: output }
+    ///
+    /// Since the terminal behind the output is unknown, `restore::restore_all` switches back to
+    /// the main screen on the terminal of the most recently entered raw mode, or on the standard
+    /// output. Use `new` for outputs with a file descriptor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the escape sequence cannot be written. Use `try_from` to handle the error
+    /// instead.
+    pub fn from(output: W) -> Self {
+        AlternateScreen::try_from(output).expect("switch to alternate screen")
+    }
+
+    /// Switch the terminal to the alternate screen, and wrap the output.
+    ///
+    /// Like `from`, this works on outputs without a file descriptor, but it reports failures to
+    /// switch screens instead of panicking.
+    pub fn try_from(output: W) -> io::Result<Self> {
+        AlternateScreen::with_fd(output, None)
+    }
+
+    /// Switch to the alternate screen on `output`, whose file descriptor is `fd` if it is known.
+    fn with_fd(mut output: W, fd: Option<RawFd>) -> io::Result<Self> {
+        output.write_all(ALTERNATE_SCREEN_SEQUENCE.as_bytes())?;
+        Ok(AlternateScreen {
+            output: Some(output),
+            id: restore::register(Mode::Escape {
+                fd,
+                enter: ALTERNATE_SCREEN_SEQUENCE,
+                exit: MAIN_SCREEN_SEQUENCE,
+            }),
+        })
+    }
+
+    /// Temporarily switch the terminal back to the main screen.
+    pub fn suspend_alternate_screen(&mut self) -> io::Result<()> {
+        write!(self, "{}", ToMainScreen)?;
+        self.flush()?;
+        restore::set_active(self.id, false);
+        Ok(())
+    }
+
+    /// Switch the terminal back to the alternate screen after `suspend_alternate_screen`.
+    pub fn activate_alternate_screen(&mut self) -> io::Result<()> {
+        write!(self, "{}", ToAlternateScreen)?;
+        self.flush()?;
+        restore::set_active(self.id, true);
+        Ok(())
+    }
+
+    /// Run a closure on the main screen, switching back to the alternate screen afterwards.
+    ///
+    /// The closure is given the inner writer, so that the wrappers it may hold can be suspended
+    /// too.
+    pub fn suspended<F, T>(&mut self, f: F) -> io::Result<T>
+        where F: FnOnce(&mut W) -> T
+    {
+        self.suspend_alternate_screen()?;
+        let res = f(&mut **self);
+        self.activate_alternate_screen()?;
+        Ok(res)
+    }
+
+    /// Switch the terminal back to the main screen, and get the inner writer back.
+    ///
+    /// Unlike dropping the wrapper, this flushes the output and reports failures to switch
+    /// screens, in which case the writer is dropped.
+    pub fn restore(mut self) -> io::Result<W> {
+        restore::unregister(self.id);
+        let mut output = self.output.take().expect("the output is only taken when restoring");
+        write!(output, "{}", ToMainScreen)?;
+        output.flush()?;
+        Ok(output)
+    }
+}
+
+impl<W: Write + AsRawFd> AlternateScreen<W> {
+    /// Switch the terminal behind `output` to the alternate screen, and wrap it.
+    ///
+    /// Unlike `from`, this reports failures to switch screens, and `restore::restore_all`
+    /// switches back to the main screen on the terminal behind `output`.
+    pub fn new(output: W) -> io::Result<Self> {
+        let fd = output.as_raw_fd();
+        AlternateScreen::with_fd(output, Some(fd))
     }
 }
 
 impl<W: Write> Drop for AlternateScreen<W> {
     fn drop(&mut self) {
-        write!(self, "{}", ToMainScreen).expect("switch to main screen");
+        // Errors cannot be reported from here; `restore` does that.
+        if let Some(ref mut output) = self.output {
+            let _ = write!(output, "{}", ToMainScreen);
+            restore::unregister(self.id);
+        }
     }
 }
 
@@ -70,22 +166,75 @@ impl<W: Write> ops::Deref for AlternateScreen<W> {
     type Target = W;
 
     fn deref(&self) -> &W {
-        &self.output
+        self.output.as_ref().expect("the output is only taken when restoring")
     }
 }
 
 impl<W: Write> ops::DerefMut for AlternateScreen<W> {
     fn deref_mut(&mut self) -> &mut W {
-        &mut self.output
+        self.output.as_mut().expect("the output is only taken when restoring")
     }
 }
 
 impl<W: Write> Write for AlternateScreen<W> {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.output.write(buf)
+        (**self).write(buf)
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        self.output.flush()
+        (**self).flush()
+    }
+}
+
+impl<W: Write + AsRawFd> AsRawFd for AlternateScreen<W> {
+    fn as_raw_fd(&self) -> RawFd {
+        (**self).as_raw_fd()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_restore() {
+        let mut screen = AlternateScreen::from(Vec::new());
+        screen.write_all(b"x").unwrap();
+
+        let output = screen.restore().unwrap();
+        assert_eq!(output, format!("{}x{}", ToAlternateScreen, ToMainScreen).into_bytes());
+    }
+
+    #[test]
+    fn test_drop_without_panicking() {
+        struct Closed;
+
+        impl Write for Closed {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::ErrorKind::BrokenPipe.into())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Err(io::ErrorKind::BrokenPipe.into())
+            }
+        }
+
+        let err = AlternateScreen::try_from(Closed).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+
+        // Switching to the alternate screen fails too, so the screen is built by hand.
+        let closed = || {
+            AlternateScreen {
+                output: Some(Closed),
+                id: restore::register(Mode::Escape {
+                    fd: None,
+                    enter: "",
+                    exit: "",
+                }),
+            }
+        };
+
+        drop(closed());
+        assert_eq!(closed().restore().err().unwrap().kind(), io::ErrorKind::BrokenPipe);
     }
 }
@@ -0,0 +1,293 @@
+//! Serial line configuration.
+//!
+//! `SerialPort` opens a TTY device, such as a USB-serial adapter, and sets up its line: speed,
+//! character size, parity, stop bits and flow control. The line is put in raw mode, so the bytes
+//! go through unchanged.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use termion::serial::{Parity, SerialConfig, SerialPort};
+//! use termion::termios::Speed;
+//! use std::io::{Read, Write};
+//!
+//! fn main() {
+//!     let mut port = SerialPort::open("/dev/ttyUSB0").unwrap();
+//!     port.configure(&SerialConfig {
+//!         speed: Speed::B115200,
+//!         parity: Parity::Even,
+//!         ..SerialConfig::default()
+//!     }).unwrap();
+//!
+//!     port.write_all(b"AT\r").unwrap();
+//!     port.drain().unwrap();
+//!
+//!     let mut buf = [0; 64];
+//!     let n = port.read(&mut buf).unwrap();
+//!     println!("{:?}", &buf[..n]);
+//! }
+//! ```
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::Path;
+
+use sys::attr::{self, TCIFLUSH, TCIOFLUSH, TCOFLUSH};
+use sys::tty::open_tty;
+use termios::{ControlFlags, InputFlags, Speed, Termios};
+
+/// The number of data bits in a character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CharSize {
+    /// Five bits.
+    Five,
+    /// Six bits.
+    Six,
+    /// Seven bits.
+    Seven,
+    /// Eight bits.
+    Eight,
+}
+
+/// The parity bit of a character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Parity {
+    /// No parity bit.
+    None,
+    /// The parity bit makes the number of set bits even.
+    Even,
+    /// The parity bit makes the number of set bits odd.
+    Odd,
+}
+
+/// The number of stop bits after a character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StopBits {
+    /// One stop bit.
+    One,
+    /// Two stop bits.
+    Two,
+}
+
+/// How the receiving side tells the sending side to pause.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FlowControl {
+    /// No flow control.
+    None,
+    /// XON/XOFF flow control, with the ctrl-q and ctrl-s characters.
+    Software,
+    /// RTS/CTS flow control, with the modem lines.
+    Hardware,
+}
+
+/// The queues to discard with `SerialPort::flush_queue`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Queue {
+    /// The data received but not read yet.
+    Input,
+    /// The data written but not transmitted yet.
+    Output,
+    /// Both.
+    Both,
+}
+
+/// The configuration of a serial line.
+///
+/// The default is 9600 baud, with eight data bits, no parity, one stop bit and no flow control.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SerialConfig {
+    /// The speed, in both directions.
+    pub speed: Speed,
+    /// The number of data bits in a character.
+    pub char_size: CharSize,
+    /// The parity bit.
+    pub parity: Parity,
+    /// The number of stop bits.
+    pub stop_bits: StopBits,
+    /// The flow control.
+    pub flow_control: FlowControl,
+}
+
+impl Default for SerialConfig {
+    fn default() -> SerialConfig {
+        SerialConfig {
+            speed: Speed::B9600,
+            char_size: CharSize::Eight,
+            parity: Parity::None,
+            stop_bits: StopBits::One,
+            flow_control: FlowControl::None,
+        }
+    }
+}
+
+impl SerialConfig {
+    /// Change the attributes to this configuration, in raw mode.
+    fn apply_attr(&self, ios: &mut Termios) -> io::Result<()> {
+        ios.make_raw();
+        ios.set_speed(self.speed)?;
+
+        let mut control = ios.control_flags();
+        // Ignore the modem lines, so the line can be used without a carrier, and enable reading.
+        control.insert(ControlFlags::CLOCAL | ControlFlags::CREAD);
+        control.remove(ControlFlags::CSIZE);
+        control.insert(match self.char_size {
+            CharSize::Five => ControlFlags::CS5,
+            CharSize::Six => ControlFlags::CS6,
+            CharSize::Seven => ControlFlags::CS7,
+            CharSize::Eight => ControlFlags::CS8,
+        });
+        control.set(ControlFlags::PARENB, self.parity != Parity::None);
+        control.set(ControlFlags::PARODD, self.parity == Parity::Odd);
+        control.set(ControlFlags::CSTOPB, self.stop_bits == StopBits::Two);
+        control.set(ControlFlags::CRTSCTS, self.flow_control == FlowControl::Hardware);
+        ios.set_control_flags(control);
+
+        let mut input = ios.input_flags();
+        input.set(InputFlags::INPCK, self.parity != Parity::None);
+        input.set(InputFlags::IXON | InputFlags::IXOFF,
+                  self.flow_control == FlowControl::Software);
+        ios.set_input_flags(input);
+
+        Ok(())
+    }
+}
+
+/// A TTY device used as a serial line.
+pub struct SerialPort {
+    file: fs::File,
+}
+
+impl SerialPort {
+    /// Open the TTY device at the given path.
+    ///
+    /// The device does not become the controlling terminal of the process, and opening it does not
+    /// wait for a carrier.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<SerialPort> {
+        open_tty(path.as_ref()).map(SerialPort::from_file)
+    }
+
+    /// Use an already opened TTY device.
+    pub fn from_file(file: fs::File) -> SerialPort {
+        SerialPort { file }
+    }
+
+    /// Get the underlying file.
+    pub fn into_file(self) -> fs::File {
+        self.file
+    }
+
+    /// Get the current attributes of the line.
+    pub fn termios(&self) -> io::Result<Termios> {
+        Termios::get(self.file.as_raw_fd())
+    }
+
+    /// Set up the line with the given configuration, in raw mode.
+    ///
+    /// The change happens right away, even if there is output left to transmit; call `drain`
+    /// first to avoid that.
+    pub fn configure(&mut self, config: &SerialConfig) -> io::Result<()> {
+        let mut ios = self.termios()?;
+        config.apply_attr(&mut ios)?;
+        ios.set(self.file.as_raw_fd())
+    }
+
+    /// Wait until everything written has been transmitted (`tcdrain`).
+    pub fn drain(&self) -> io::Result<()> {
+        attr::drain(self.file.as_raw_fd())
+    }
+
+    /// Discard the data received but not read, written but not transmitted, or both
+    /// (`tcflush`).
+    pub fn flush_queue(&self, queue: Queue) -> io::Result<()> {
+        attr::flush(self.file.as_raw_fd(),
+                    match queue {
+                        Queue::Input => TCIFLUSH,
+                        Queue::Output => TCOFLUSH,
+                        Queue::Both => TCIOFLUSH,
+                    })
+    }
+
+    /// Transmit a break, zero bits for between a quarter and half a second (`tcsendbreak`).
+    pub fn send_break(&self) -> io::Result<()> {
+        attr::send_break(self.file.as_raw_fd())
+    }
+}
+
+impl Read for SerialPort {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.file.read(buf)
+    }
+}
+
+impl Write for SerialPort {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+impl AsRawFd for SerialPort {
+    fn as_raw_fd(&self) -> RawFd {
+        self.file.as_raw_fd()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sys::tty::open_pty;
+    use termios::LocalFlags;
+
+    #[test]
+    fn test_serial_port() {
+        let (mut master, path) = open_pty().unwrap();
+        let mut port = SerialPort::open(&path).unwrap();
+
+        let config = SerialConfig {
+            speed: Speed::B115200,
+            char_size: CharSize::Seven,
+            parity: Parity::Odd,
+            stop_bits: StopBits::Two,
+            flow_control: FlowControl::Software,
+        };
+
+        // Pseudo-terminals always use eight bits without parity, so check the attributes before
+        // they are set.
+        let mut ios = port.termios().unwrap();
+        config.apply_attr(&mut ios).unwrap();
+        let control = ios.control_flags();
+        assert_eq!(control & ControlFlags::CSIZE, ControlFlags::CS7);
+        assert!(control.contains(ControlFlags::PARENB | ControlFlags::PARODD | ControlFlags::CSTOPB));
+        assert!(ios.input_flags().contains(InputFlags::IXON | InputFlags::IXOFF));
+        assert!(!ios.local_flags().intersects(LocalFlags::ICANON | LocalFlags::ECHO));
+
+        port.configure(&config).unwrap();
+        let ios = port.termios().unwrap();
+        assert_eq!(ios.input_speed(), Some(Speed::B115200));
+        assert_eq!(ios.output_speed(), Some(Speed::B115200));
+        assert!(ios.input_flags().contains(InputFlags::IXON | InputFlags::IXOFF));
+
+        port.configure(&SerialConfig::default()).unwrap();
+        let ios = port.termios().unwrap();
+        assert_eq!(ios.output_speed(), Some(Speed::B9600));
+        assert!(!ios.input_flags().intersects(InputFlags::IXON | InputFlags::IXOFF));
+
+        // The line is raw, so bytes go through unchanged in both directions.
+        master.write_all(b"ping\r").unwrap();
+        let mut buf = [0; 5];
+        port.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"ping\r");
+
+        port.write_all(b"pong\n").unwrap();
+        port.drain().unwrap();
+        master.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"pong\n");
+
+        port.flush_queue(Queue::Both).unwrap();
+        port.send_break().unwrap();
+    }
+}
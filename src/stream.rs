@@ -0,0 +1,138 @@
+//! Asynchronous terminal events for the tokio runtime.
+//!
+//! This module is only available with the `tokio` feature enabled.
+//!
+//! Rather than dedicating a thread to blocking reads, `EventStream` waits for the TTY to become
+//! readable through the tokio reactor, so it can be combined with timers, sockets and other
+//! futures, for example through `tokio::select!`.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! extern crate futures_core;
+//! extern crate termion;
+//! extern crate tokio;
+//!
+//! use futures_core::Stream;
+//! use std::pin::Pin;
+//! use std::future;
+//! use termion::stream::EventStream;
+//!
+//! fn main() {
+//!     let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+//!     let _guard = rt.enter();
+//!     let mut events = EventStream::new().unwrap();
+//!
+//!     let next = future::poll_fn(|cx| Pin::new(&mut events).poll_next(cx));
+//!     println!("{:?}", rt.block_on(next));
+//! }
+//! ```
+
+use std::fs;
+use std::io::{self, Read};
+use std::os::unix::io::AsRawFd;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use tokio::io::unix::AsyncFd;
+use tokio::signal::unix::{signal, Signal, SignalKind};
+
+use event::Event;
+use input::EventBuffer;
+use restore::resume_all;
+use sys::signal::SIGCONT;
+use sys::size::terminal_size;
+use sys::tty::{get_tty, set_nonblocking};
+
+/// A stream of events read from the TTY.
+///
+/// Window size changes are reported as `Event::Resize`, interleaved with the input events. So is
+/// continuing the program after it was stopped, once the terminal modes are set up again, so the
+/// screen can be redrawn.
+///
+/// The stream must be created and polled from within a tokio runtime which has both the I/O and
+/// the signal drivers enabled.
+pub struct EventStream {
+    tty: AsyncFd<fs::File>,
+    buffer: EventBuffer,
+    resize: Signal,
+    resume: Signal,
+    was_nonblocking: bool,
+}
+
+impl EventStream {
+    /// Open the TTY device and create a stream of its events.
+    pub fn new() -> io::Result<EventStream> {
+        EventStream::from_tty(get_tty()?)
+    }
+
+    /// Create a stream of events read from the given terminal device.
+    ///
+    /// The file is switched to non-blocking mode, until the stream is dropped.
+    pub fn from_tty(tty: fs::File) -> io::Result<EventStream> {
+        let was_nonblocking = set_nonblocking(tty.as_raw_fd(), true)?;
+        // `AsyncFd::new` is deprecated as unsound since tokio 1.53. The file owns its descriptor,
+        // which stays open for as long as it is registered, as `register` requires.
+        let tty = unsafe { AsyncFd::register(tty)? };
+
+        Ok(EventStream {
+            tty,
+            buffer: EventBuffer::new(),
+            resize: signal(SignalKind::window_change())?,
+            resume: signal(SignalKind::from_raw(SIGCONT))?,
+            was_nonblocking,
+        })
+    }
+}
+
+impl Drop for EventStream {
+    fn drop(&mut self) {
+        let _ = set_nonblocking(self.tty.get_ref().as_raw_fd(), self.was_nonblocking);
+    }
+}
+
+impl Stream for EventStream {
+    type Item = io::Result<Event>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<io::Result<Event>>> {
+        let this = self.get_mut();
+
+        if let Poll::Ready(Some(())) = this.resume.poll_recv(cx) {
+            // The terminal may have been changed while the program was stopped.
+            resume_all();
+            let event = terminal_size().map(|(w, h)| Event::Resize(w, h));
+            return Poll::Ready(Some(event));
+        }
+
+        if let Poll::Ready(Some(())) = this.resize.poll_recv(cx) {
+            let event = terminal_size().map(|(w, h)| Event::Resize(w, h));
+            return Poll::Ready(Some(event));
+        }
+
+        let mut buf = [0; 1024];
+        loop {
+            if let Some((event, _)) = this.buffer.next_event(false) {
+                return Poll::Ready(Some(Ok(event)));
+            }
+
+            let mut guard = match this.tty.poll_read_ready(cx) {
+                Poll::Ready(Ok(guard)) => guard,
+                Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e))),
+                Poll::Pending => return Poll::Pending,
+            };
+
+            match guard.try_io(|tty| tty.get_ref().read(&mut buf)) {
+                Ok(Ok(0)) => return Poll::Ready(None),
+                Ok(Ok(n)) => this.buffer.extend(&buf[..n]),
+                Ok(Err(e)) => return Poll::Ready(Some(Err(e))),
+                Err(_would_block) => {
+                    // Everything available has been read, so a lone ESC is the escape key.
+                    if let Some((event, _)) = this.buffer.next_event(true) {
+                        return Poll::Ready(Some(Ok(event)));
+                    }
+                }
+            }
+        }
+    }
+}
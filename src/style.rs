@@ -1,6 +1,26 @@
 //! Text styling management.
+//!
+//! The unit structs below are written one at a time. A `Style` holds the colors and attributes of
+//! some text as a whole, so that it can be combined with another one, and so that a renderer can
+//! write only the difference with the style in effect.
+//!
+//! # Example
+//!
+//! ```rust
+//! use termion::color;
+//! use termion::style::{Attributes, Style};
+//!
+//! let base = Style::new().fg(color::White).bg(color::Blue);
+//! let title = base.add_attributes(Attributes::BOLD | Attributes::UNDERLINE);
+//!
+//! // Only the attributes are turned off, the colors stay.
+//! assert_eq!(title.diff(&base).to_string(), "\x1B[22;24m");
+//! ```
 
 use std::fmt;
+use std::ops;
+
+use color::AnyColor;
 
 derive_csi_sequence!("Reset SGR parameters.", Reset, "m");
 derive_csi_sequence!("Bold text.", Bold, "1m");
@@ -20,3 +40,344 @@ derive_csi_sequence!("Undo crossed out text (not widely supported).",
                      NoCrossedOut,
                      "29m");
 derive_csi_sequence!("Framed text (not widely supported).", Framed, "51m");
+
+/// A set of text attributes, such as `Attributes::BOLD | Attributes::ITALIC`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Attributes(u8);
+
+impl Attributes {
+    /// Bold text.
+    pub const BOLD: Attributes = Attributes(1);
+    /// Fainted text.
+    pub const FAINT: Attributes = Attributes(1 << 1);
+    /// Italic text.
+    pub const ITALIC: Attributes = Attributes(1 << 2);
+    /// Underlined text.
+    pub const UNDERLINE: Attributes = Attributes(1 << 3);
+    /// Blinking text.
+    pub const BLINK: Attributes = Attributes(1 << 4);
+    /// Inverted colors.
+    pub const INVERT: Attributes = Attributes(1 << 5);
+    /// Crossed out text.
+    pub const CROSSED_OUT: Attributes = Attributes(1 << 6);
+    /// Framed text.
+    pub const FRAMED: Attributes = Attributes(1 << 7);
+
+    /// No attributes.
+    pub fn empty() -> Attributes {
+        Attributes(0)
+    }
+
+    /// Check whether there are no attributes.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Check whether all the attributes of `other` are in the set.
+    pub fn contains(self, other: Attributes) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Check whether some attributes of `other` are in the set.
+    pub fn intersects(self, other: Attributes) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// Add the attributes of `other` to the set.
+    pub fn insert(&mut self, other: Attributes) {
+        self.0 |= other.0;
+    }
+
+    /// Remove the attributes of `other` from the set.
+    pub fn remove(&mut self, other: Attributes) {
+        self.0 &= !other.0;
+    }
+}
+
+impl ops::BitOr for Attributes {
+    type Output = Attributes;
+
+    fn bitor(self, other: Attributes) -> Attributes {
+        Attributes(self.0 | other.0)
+    }
+}
+
+impl ops::BitAnd for Attributes {
+    type Output = Attributes;
+
+    fn bitand(self, other: Attributes) -> Attributes {
+        Attributes(self.0 & other.0)
+    }
+}
+
+impl ops::Sub for Attributes {
+    type Output = Attributes;
+
+    /// The attributes of `self` which are not in `other`.
+    fn sub(self, other: Attributes) -> Attributes {
+        Attributes(self.0 & !other.0)
+    }
+}
+
+/// The attributes, with the SGR parameters setting and unsetting them.
+///
+/// Bold and faint are unset together.
+const SGR_ATTRIBUTES: [(Attributes, &str, &str); 8] = [(Attributes::BOLD, "1", "22"),
+                                                       (Attributes::FAINT, "2", "22"),
+                                                       (Attributes::ITALIC, "3", "23"),
+                                                       (Attributes::UNDERLINE, "4", "24"),
+                                                       (Attributes::BLINK, "5", "25"),
+                                                       (Attributes::INVERT, "7", "27"),
+                                                       (Attributes::CROSSED_OUT, "9", "29"),
+                                                       (Attributes::FRAMED, "51", "54")];
+
+/// The colors and attributes of text.
+///
+/// The colors which are `None` are left as they are when the style is patched onto another one,
+/// and are the default colors otherwise. Likewise, the `removed` attributes are only taken off
+/// the other style by `patch`.
+///
+/// Written with `{}`, a style resets everything, then sets its colors and attributes. Use `diff`
+/// to go from one style to another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Style {
+    /// The foreground color.
+    pub fg: Option<AnyColor>,
+    /// The background color.
+    pub bg: Option<AnyColor>,
+    /// The color of the underline (not widely supported).
+    pub underline_color: Option<AnyColor>,
+    /// The attributes of the text.
+    pub attributes: Attributes,
+    /// The attributes taken off by `patch`.
+    pub removed: Attributes,
+}
+
+impl Style {
+    /// A style without colors nor attributes.
+    pub fn new() -> Style {
+        Style::default()
+    }
+
+    /// Set the foreground color.
+    pub fn fg<C: Into<AnyColor>>(mut self, color: C) -> Style {
+        self.fg = Some(color.into());
+        self
+    }
+
+    /// Set the background color.
+    pub fn bg<C: Into<AnyColor>>(mut self, color: C) -> Style {
+        self.bg = Some(color.into());
+        self
+    }
+
+    /// Set the color of the underline.
+    pub fn underline_color<C: Into<AnyColor>>(mut self, color: C) -> Style {
+        self.underline_color = Some(color.into());
+        self
+    }
+
+    /// Add attributes.
+    pub fn add_attributes(mut self, attributes: Attributes) -> Style {
+        self.attributes.insert(attributes);
+        self.removed.remove(attributes);
+        self
+    }
+
+    /// Remove attributes, including from the styles this one is patched onto.
+    pub fn remove_attributes(mut self, attributes: Attributes) -> Style {
+        self.attributes.remove(attributes);
+        self.removed.insert(attributes);
+        self
+    }
+
+    /// Combine this style with `other`, whose colors and attributes win.
+    pub fn patch(self, other: Style) -> Style {
+        Style {
+            fg: other.fg.or(self.fg),
+            bg: other.bg.or(self.bg),
+            underline_color: other.underline_color.or(self.underline_color),
+            attributes: (self.attributes - other.removed) | other.attributes,
+            removed: (self.removed - other.attributes) | other.removed,
+        }
+    }
+
+    /// The shortest sequence changing the style in effect from this one to `to`.
+    ///
+    /// It is empty if the styles look the same, and starts with a reset when that is shorter.
+    pub fn diff<'a>(&'a self, to: &'a Style) -> Diff<'a> {
+        Diff { from: self, to }
+    }
+
+    /// The SGR parameters setting this style over the default one.
+    fn params(&self) -> Vec<String> {
+        let mut params = Vec::new();
+        for &(attribute, set, _) in &SGR_ATTRIBUTES {
+            if self.attributes.contains(attribute) {
+                params.push(set.to_owned());
+            }
+        }
+        for &(color, base) in &[(self.fg, 30), (self.bg, 40), (self.underline_color, 50)] {
+            if let Some(color) = effective(color) {
+                params.push(color.sgr_params(base));
+            }
+        }
+        params
+    }
+
+    /// The SGR parameters setting `to` over this style.
+    fn diff_params(&self, to: &Style) -> Vec<String> {
+        let mut params = Vec::new();
+
+        let mut set = to.attributes - self.attributes;
+        let unset = self.attributes - to.attributes;
+        let bold_or_faint = Attributes::BOLD | Attributes::FAINT;
+        if unset.intersects(bold_or_faint) {
+            params.push("22".to_owned());
+            set.insert(to.attributes & bold_or_faint);
+        }
+        for &(attribute, _, reset) in &SGR_ATTRIBUTES {
+            if unset.contains(attribute) && !bold_or_faint.contains(attribute) {
+                params.push(reset.to_owned());
+            }
+        }
+        for &(attribute, sgr, _) in &SGR_ATTRIBUTES {
+            if set.contains(attribute) {
+                params.push(sgr.to_owned());
+            }
+        }
+
+        let colors = [(self.fg, to.fg, 30), (self.bg, to.bg, 40),
+                      (self.underline_color, to.underline_color, 50)];
+        for &(from, to, base) in &colors {
+            let (from, to) = (effective(from), effective(to));
+            if from != to {
+                params.push(to.unwrap_or(AnyColor::Reset).sgr_params(base));
+            }
+        }
+
+        params
+    }
+}
+
+/// The color set by a style, where `None` and `Reset` both give the default color.
+fn effective(color: Option<AnyColor>) -> Option<AnyColor> {
+    color.filter(|&color| color != AnyColor::Reset)
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut params = self.params();
+        params.insert(0, "0".to_owned());
+        write!(f, csi!("{}m"), params.join(";"))
+    }
+}
+
+/// The sequence going from a style to another, as returned by `Style::diff`.
+#[derive(Debug, Clone, Copy)]
+pub struct Diff<'a> {
+    from: &'a Style,
+    to: &'a Style,
+}
+
+impl<'a> fmt::Display for Diff<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let params = self.from.diff_params(self.to);
+        if params.is_empty() {
+            return Ok(());
+        }
+
+        let mut reset = self.to.params();
+        let changes = params.join(";");
+        if reset.is_empty() {
+            return write!(f, csi!("m"));
+        }
+        reset.insert(0, "0".to_owned());
+        let reset = reset.join(";");
+
+        if reset.len() < changes.len() {
+            write!(f, csi!("{}m"), reset)
+        } else {
+            write!(f, csi!("{}m"), changes)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use color::{self, AnsiValue, Rgb};
+
+    #[test]
+    fn test_attributes() {
+        let mut attributes = Attributes::BOLD | Attributes::ITALIC;
+        assert!(attributes.contains(Attributes::BOLD));
+        assert!(!attributes.contains(Attributes::BOLD | Attributes::BLINK));
+        assert!(attributes.intersects(Attributes::BOLD | Attributes::BLINK));
+
+        attributes.remove(Attributes::BOLD);
+        assert_eq!(attributes, Attributes::ITALIC);
+        assert_eq!(attributes - Attributes::ITALIC, Attributes::empty());
+        assert!(Attributes::default().is_empty());
+    }
+
+    #[test]
+    fn test_patch() {
+        let base = Style::new().fg(color::Red).bg(Rgb(1, 2, 3)).add_attributes(Attributes::BOLD);
+        let patch = Style::new()
+            .fg(AnsiValue(7))
+            .add_attributes(Attributes::ITALIC)
+            .remove_attributes(Attributes::BOLD);
+        let style = base.patch(patch);
+
+        assert_eq!(style.fg, Some(AnyColor::Ansi(AnsiValue(7))));
+        assert_eq!(style.bg, Some(AnyColor::Rgb(Rgb(1, 2, 3))));
+        assert_eq!(style.attributes, Attributes::ITALIC);
+        assert_eq!(style.removed, Attributes::BOLD);
+
+        assert_eq!(style.patch(Style::new()), style);
+        assert_eq!(style.patch(Style::new().add_attributes(Attributes::BOLD)).attributes,
+                   Attributes::BOLD | Attributes::ITALIC);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Style::new().to_string(), "\x1B[0m");
+        let style = Style::new()
+            .fg(color::LightRed)
+            .bg(AnsiValue(100))
+            .underline_color(Rgb(1, 2, 3))
+            .add_attributes(Attributes::BOLD | Attributes::FRAMED);
+        assert_eq!(style.to_string(), "\x1B[0;1;51;91;48;5;100;58:2::1:2:3m");
+        assert_eq!(Style::new().underline_color(color::Red).to_string(), "\x1B[0;58:5:1m");
+    }
+
+    #[test]
+    fn test_diff() {
+        let diff = |from: Style, to: Style| from.diff(&to).to_string();
+        let plain = Style::new();
+        let bold = Style::new().add_attributes(Attributes::BOLD);
+        let red = Style::new().fg(color::Red);
+
+        assert_eq!(diff(plain, plain), "");
+        assert_eq!(diff(red, red.fg(color::Reset)), "\x1B[m");
+        assert_eq!(diff(plain, red.fg(color::Reset)), "");
+        assert_eq!(diff(plain, bold), "\x1B[1m");
+        assert_eq!(diff(bold, plain), "\x1B[m");
+        assert_eq!(diff(red, red.add_attributes(Attributes::ITALIC)), "\x1B[3m");
+        assert_eq!(diff(red, red.bg(color::Blue)), "\x1B[44m");
+        assert_eq!(diff(red.bg(color::Blue), red), "\x1B[49m");
+        assert_eq!(diff(red.underline_color(color::Blue), red), "\x1B[59m");
+
+        // Unsetting bold unsets faint too.
+        let faint = red.add_attributes(Attributes::FAINT);
+        assert_eq!(diff(faint.add_attributes(Attributes::BOLD), faint), "\x1B[22;2m");
+
+        // A reset is shorter.
+        let rgb = Style::new().fg(Rgb(1, 2, 3)).bg(Rgb(4, 5, 6));
+        let styled = rgb.add_attributes(Attributes::ITALIC | Attributes::UNDERLINE |
+                                        Attributes::BLINK);
+        assert_eq!(diff(styled, Style::new().add_attributes(Attributes::BLINK)), "\x1B[0;5m");
+        assert_eq!(diff(styled, rgb.add_attributes(Attributes::ITALIC)), "\x1B[24;25m");
+    }
+}
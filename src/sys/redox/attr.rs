@@ -1,11 +1,28 @@
 use std::io;
+use std::os::unix::io::RawFd;
 
 use super::{cvt, syscall, Termios};
 
-pub fn get_terminal_attr() -> io::Result<Termios> {
+pub use super::redox_termios::{tcflag_t as Flag, tcflag_t as Speed};
+
+pub use super::redox_termios::{BRKINT, ICRNL, IGNBRK, IGNCR, IGNPAR, INLCR, INPCK, ISTRIP, IXOFF,
+                               IXON, PARMRK};
+pub use super::redox_termios::{OCRNL, ONLCR, ONLRET, ONOCR, OPOST};
+pub use super::redox_termios::{CLOCAL, CREAD, CS5, CS6, CS7, CS8, CSIZE, CSTOPB, HUPCL, PARENB,
+                               PARODD};
+pub use super::redox_termios::{ECHO, ECHOE, ECHOK, ECHONL, ICANON, IEXTEN, ISIG, NOFLSH, TOSTOP};
+pub use super::redox_termios::{VEOF, VEOL, VERASE, VINTR, VKILL, VMIN, VQUIT, VSTART, VSTOP,
+                               VSUSP, VTIME};
+pub use super::redox_termios::{B0, B110, B115200, B1200, B134, B150, B1800, B19200, B200, B230400,
+                               B2400, B300, B38400, B4800, B50, B57600, B600, B75, B9600};
+
+/// The bits of the control flags holding the speed, which is the same for input and output.
+const CBAUD: Flag = 0o37;
+
+pub fn get_terminal_attr(fd: RawFd) -> io::Result<Termios> {
     let mut termios = Termios::default();
 
-    let fd = cvt(syscall::dup(0, b"termios"))?;
+    let fd = cvt(syscall::dup(fd as usize, b"termios"))?;
     let res = cvt(syscall::read(fd, &mut termios));
     let _ = syscall::close(fd);
 
@@ -16,8 +33,8 @@ pub fn get_terminal_attr() -> io::Result<Termios> {
     }
 }
 
-pub fn set_terminal_attr(termios: &Termios) -> io::Result<()> {
-    let fd = cvt(syscall::dup(0, b"termios"))?;
+pub fn set_terminal_attr(fd: RawFd, termios: &Termios) -> io::Result<()> {
+    let fd = cvt(syscall::dup(fd as usize, b"termios"))?;
     let res = cvt(syscall::write(fd, termios));
     let _ = syscall::close(fd);
 
@@ -31,3 +48,21 @@ pub fn set_terminal_attr(termios: &Termios) -> io::Result<()> {
 pub fn raw_terminal_attr(ios: &mut Termios) {
     ios.make_raw()
 }
+
+pub fn input_speed(termios: &Termios) -> Speed {
+    termios.c_cflag & CBAUD
+}
+
+pub fn output_speed(termios: &Termios) -> Speed {
+    termios.c_cflag & CBAUD
+}
+
+pub fn set_input_speed(termios: &mut Termios, speed: Speed) -> io::Result<()> {
+    termios.c_cflag = (termios.c_cflag & !CBAUD) | speed;
+    Ok(())
+}
+
+pub fn set_output_speed(termios: &mut Termios, speed: Speed) -> io::Result<()> {
+    termios.c_cflag = (termios.c_cflag & !CBAUD) | speed;
+    Ok(())
+}
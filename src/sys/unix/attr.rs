@@ -1,24 +1,42 @@
 use std::{io, mem};
+use std::os::unix::io::RawFd;
 
-use super::{cvt, Termios};
-use super::libc::c_int;
+use super::{cvt, not_a_tty, Termios};
+use super::libc::{self, c_int};
 
-pub fn get_terminal_attr() -> io::Result<Termios> {
+pub use super::libc::{tcflag_t as Flag, speed_t as Speed};
+
+pub use super::libc::{BRKINT, ICRNL, IGNBRK, IGNCR, IGNPAR, INLCR, INPCK, ISTRIP, IXOFF, IXON,
+                      PARMRK};
+pub use super::libc::{OCRNL, ONLCR, ONLRET, ONOCR, OPOST};
+pub use super::libc::{CLOCAL, CREAD, CRTSCTS, CS5, CS6, CS7, CS8, CSIZE, CSTOPB, HUPCL, PARENB,
+                      PARODD};
+pub use super::libc::{ECHO, ECHOE, ECHOK, ECHONL, ICANON, IEXTEN, ISIG, NOFLSH, TOSTOP};
+pub use super::libc::{VEOF, VEOL, VERASE, VINTR, VKILL, VMIN, VQUIT, VSTART, VSTOP, VSUSP,
+                      VTIME};
+pub use super::libc::{TCIFLUSH, TCIOFLUSH, TCOFLUSH};
+pub use super::libc::{B0, B110, B115200, B1200, B134, B150, B1800, B19200, B200, B230400, B2400,
+                      B300, B38400, B4800, B50, B57600, B600, B75, B9600};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub use super::libc::{B1000000, B1152000, B1500000, B2000000, B2500000, B3000000, B3500000,
+                      B4000000, B460800, B500000, B576000, B921600};
+
+pub fn get_terminal_attr(fd: RawFd) -> io::Result<Termios> {
     extern "C" {
         pub fn tcgetattr(fd: c_int, termptr: *mut Termios) -> c_int;
     }
     unsafe {
         let mut termios = mem::zeroed();
-        cvt(tcgetattr(0, &mut termios))?;
+        cvt(tcgetattr(fd, &mut termios)).map_err(not_a_tty)?;
         Ok(termios)
     }
 }
 
-pub fn set_terminal_attr(termios: &Termios) -> io::Result<()> {
+pub fn set_terminal_attr(fd: RawFd, termios: &Termios) -> io::Result<()> {
     extern "C" {
         pub fn tcsetattr(fd: c_int, opt: c_int, termptr: *const Termios) -> c_int;
     }
-    cvt(unsafe { tcsetattr(0, 0, termios) }).and(Ok(()))
+    cvt(unsafe { tcsetattr(fd, 0, termios) }).map_err(not_a_tty).and(Ok(()))
 }
 
 pub fn raw_terminal_attr(termios: &mut Termios) {
@@ -27,3 +45,34 @@ pub fn raw_terminal_attr(termios: &mut Termios) {
     }
     unsafe { cfmakeraw(termios) }
 }
+
+pub fn input_speed(termios: &Termios) -> Speed {
+    unsafe { libc::cfgetispeed(termios) }
+}
+
+pub fn output_speed(termios: &Termios) -> Speed {
+    unsafe { libc::cfgetospeed(termios) }
+}
+
+pub fn set_input_speed(termios: &mut Termios, speed: Speed) -> io::Result<()> {
+    cvt(unsafe { libc::cfsetispeed(termios, speed) }).and(Ok(()))
+}
+
+pub fn set_output_speed(termios: &mut Termios, speed: Speed) -> io::Result<()> {
+    cvt(unsafe { libc::cfsetospeed(termios, speed) }).and(Ok(()))
+}
+
+/// Wait until all the output written to `fd` has been transmitted.
+pub fn drain(fd: RawFd) -> io::Result<()> {
+    cvt(unsafe { libc::tcdrain(fd) }).and(Ok(()))
+}
+
+/// Discard the data received but not read, written but not transmitted, or both.
+pub fn flush(fd: RawFd, queue: c_int) -> io::Result<()> {
+    cvt(unsafe { libc::tcflush(fd, queue) }).and(Ok(()))
+}
+
+/// Transmit a break, a stream of zero bits lasting between a quarter and half a second.
+pub fn send_break(fd: RawFd) -> io::Result<()> {
+    cvt(unsafe { libc::tcsendbreak(fd, 0) }).and(Ok(()))
+}
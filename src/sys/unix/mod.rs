@@ -1,10 +1,15 @@
 extern crate libc;
+extern crate signal_hook_registry;
 
 use std::io;
 
+use error::Error;
+
 pub use self::libc::termios as Termios;
 
 pub mod attr;
+pub mod poll;
+pub mod signal;
 pub mod size;
 pub mod tty;
 
@@ -30,4 +35,12 @@ fn cvt<T: IsMinusOne>(t: T) -> io::Result<T> {
         Ok(t)
     }
 }
+
+/// Report the errors meaning that there is no terminal as `Error::NotATty`.
+fn not_a_tty(err: io::Error) -> io::Error {
+    match err.raw_os_error() {
+        Some(libc::ENOTTY) | Some(libc::ENXIO) => Error::NotATty.into(),
+        _ => err,
+    }
+}
 // } End of support functions
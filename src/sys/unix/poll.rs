@@ -0,0 +1,57 @@
+use std::io;
+use std::os::unix::io::RawFd;
+use std::time::Duration;
+
+use super::libc::{self, c_int};
+
+/// Wait until any of the file descriptors is readable, or until the timeout expires.
+///
+/// Returns which of the descriptors are ready. Hang ups count as readable, so that the following
+/// read reports them. A signal interrupting the wait is not an error, and is reported as nothing
+/// being ready.
+pub fn poll(fds: &[RawFd], timeout: Option<Duration>) -> io::Result<Vec<bool>> {
+    let mut pollfds: Vec<libc::pollfd> = fds.iter()
+        .map(|&fd| libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        })
+        .collect();
+
+    let timeout = timeout.map_or(-1, timeout_millis);
+
+    let res = unsafe { libc::poll(pollfds.as_mut_ptr(), pollfds.len() as libc::nfds_t, timeout) };
+    if res < 0 {
+        let err = io::Error::last_os_error();
+        return if err.kind() == io::ErrorKind::Interrupted {
+            Ok(vec![false; fds.len()])
+        } else {
+            Err(err)
+        };
+    }
+
+    Ok(pollfds.iter().map(|p| p.revents & (libc::POLLIN | libc::POLLHUP | libc::POLLERR) != 0).collect())
+}
+
+/// The timeout of `poll`, in milliseconds.
+///
+/// It is rounded up, so that we do not wake up just before the deadline, and clamped, since a
+/// negative timeout means waiting forever.
+fn timeout_millis(t: Duration) -> c_int {
+    (t.as_secs().min(c_int::MAX as u64) as c_int)
+        .saturating_mul(1000)
+        .saturating_add(t.subsec_nanos().div_ceil(1_000_000) as c_int)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_timeout_millis() {
+        assert_eq!(timeout_millis(Duration::from_micros(1500)), 2);
+        assert_eq!(timeout_millis(Duration::from_secs(3)), 3000);
+        assert_eq!(timeout_millis(Duration::from_secs(u64::from(u32::MAX) + 1)), c_int::MAX);
+        assert_eq!(timeout_millis(Duration::MAX), c_int::MAX);
+    }
+}
@@ -0,0 +1,137 @@
+use std::io;
+use std::mem;
+use std::os::unix::io::RawFd;
+use std::ptr;
+
+use super::cvt;
+use super::libc::{self, c_int, c_void};
+use super::signal_hook_registry::{register, unregister, SigId};
+
+pub use super::libc::{SIGCONT, SIGHUP, SIGINT, SIGQUIT, SIGTERM, SIGTSTP, SIGWINCH};
+
+/// A non-blocking pipe, used to wake up a thread waiting in `poll`.
+pub struct Pipe {
+    read: RawFd,
+    write: RawFd,
+}
+
+impl Pipe {
+    pub fn new() -> io::Result<Pipe> {
+        let mut fds = [0; 2];
+        cvt(unsafe { libc::pipe(fds.as_mut_ptr()) })?;
+        let pipe = Pipe {
+            read: fds[0],
+            write: fds[1],
+        };
+
+        for &fd in &fds {
+            unsafe {
+                cvt(libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC))?;
+                let flags = cvt(libc::fcntl(fd, libc::F_GETFL))?;
+                cvt(libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK))?;
+            }
+        }
+
+        Ok(pipe)
+    }
+
+    /// The end of the pipe to wait on.
+    pub fn fd(&self) -> RawFd {
+        self.read
+    }
+
+    /// Write a byte to the pipe.
+    ///
+    /// A full pipe is not an error, as the reader has not been woken up yet anyway.
+    pub fn wake(&self, byte: u8) {
+        write_byte(self.write, byte)
+    }
+
+    /// Read all the bytes written to the pipe so far.
+    pub fn drain(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut buf = [0u8; 64];
+        loop {
+            let n = unsafe { libc::read(self.read, buf.as_mut_ptr() as *mut c_void, buf.len()) };
+            if n <= 0 {
+                return bytes;
+            }
+            bytes.extend_from_slice(&buf[..n as usize]);
+        }
+    }
+}
+
+impl Drop for Pipe {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.read);
+            libc::close(self.write);
+        }
+    }
+}
+
+/// Write a single byte. This is async-signal-safe.
+fn write_byte(fd: RawFd, byte: u8) {
+    unsafe {
+        libc::write(fd, &byte as *const u8 as *const c_void, 1);
+    }
+}
+
+/// A pipe receiving the number of every delivered signal it was created for.
+pub struct SignalPipe {
+    pipe: Pipe,
+    ids: Vec<SigId>,
+}
+
+impl SignalPipe {
+    pub fn new(signals: &[c_int]) -> io::Result<SignalPipe> {
+        let mut pipe = SignalPipe {
+            pipe: Pipe::new()?,
+            ids: Vec::new(),
+        };
+
+        for &signal in signals {
+            let fd = pipe.pipe.write;
+            let id = unsafe { register(signal, move || write_byte(fd, signal as u8)) }?;
+            pipe.ids.push(id);
+        }
+
+        Ok(pipe)
+    }
+
+    /// The end of the pipe to wait on.
+    pub fn fd(&self) -> RawFd {
+        self.pipe.fd()
+    }
+
+    /// The signals delivered since the last call.
+    pub fn drain(&self) -> Vec<c_int> {
+        self.pipe.drain().into_iter().map(c_int::from).collect()
+    }
+}
+
+impl Drop for SignalPipe {
+    fn drop(&mut self) {
+        for id in self.ids.drain(..) {
+            unregister(id);
+        }
+    }
+}
+
+/// Act on a signal as if it had no handler, which for most signals terminates the process.
+///
+/// The handlers are put back afterwards, which matters for signals which do not terminate the
+/// process, such as `SIGTSTP`.
+pub fn raise_default(signal: c_int) {
+    unsafe {
+        let mut default: libc::sigaction = mem::zeroed();
+        default.sa_sigaction = libc::SIG_DFL;
+        let mut prev: libc::sigaction = mem::zeroed();
+
+        if libc::sigaction(signal, &default, &mut prev) != 0 {
+            return;
+        }
+        libc::raise(signal);
+        libc::sigaction(signal, &prev, ptr::null_mut());
+    }
+}
@@ -1,7 +1,9 @@
 use std::{fs, io};
-use std::os::unix::io::AsRawFd;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::Path;
 
-use super::libc;
+use super::{cvt, libc, not_a_tty};
 
 
 /// Is this stream a TTY?
@@ -12,6 +14,58 @@ pub fn is_tty<T: AsRawFd>(stream: &T) -> bool {
 /// Get the TTY device.
 ///
 /// This allows for getting stdio representing _only_ the TTY, and not other streams.
+///
+/// Without a controlling terminal, this fails with `Error::NotATty`.
 pub fn get_tty() -> io::Result<fs::File> {
-    fs::OpenOptions::new().read(true).write(true).open("/dev/tty")
+    fs::OpenOptions::new().read(true).write(true).open("/dev/tty").map_err(not_a_tty)
+}
+
+/// Open a TTY device, without making it the controlling terminal of the process.
+///
+/// The open does not wait for the modem to be connected, and the file is left in blocking mode.
+pub fn open_tty(path: &Path) -> io::Result<fs::File> {
+    let file = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .custom_flags(libc::O_NOCTTY | libc::O_NONBLOCK)
+        .open(path)?;
+    set_nonblocking(file.as_raw_fd(), false)?;
+    Ok(file)
+}
+
+/// Open a new pseudo-terminal, returning its master side and the path of its slave side.
+#[cfg(test)]
+pub fn open_pty() -> io::Result<(fs::File, ::std::path::PathBuf)> {
+    use std::ffi::CStr;
+    use std::os::unix::io::FromRawFd;
+
+    unsafe {
+        let fd = cvt(libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY))?;
+        let master = fs::File::from_raw_fd(fd);
+        cvt(libc::grantpt(fd))?;
+        cvt(libc::unlockpt(fd))?;
+
+        let name = libc::ptsname(fd);
+        if name.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+        let name = CStr::from_ptr(name).to_string_lossy().into_owned();
+        Ok((master, name.into()))
+    }
+}
+
+/// Set whether reads from the file descriptor return immediately when no data is available.
+///
+/// Returns whether they did before, so the previous mode can be restored.
+pub fn set_nonblocking(fd: RawFd, nonblocking: bool) -> io::Result<bool> {
+    unsafe {
+        let old = cvt(libc::fcntl(fd, libc::F_GETFL))?;
+        let flags = if nonblocking {
+            old | libc::O_NONBLOCK
+        } else {
+            old & !libc::O_NONBLOCK
+        };
+        cvt(libc::fcntl(fd, libc::F_SETFL, flags))?;
+        Ok(old & libc::O_NONBLOCK != 0)
+    }
 }
@@ -0,0 +1,415 @@
+//! Reading the terminfo database.
+//!
+//! The escape sequences of termion are those of xterm, which most terminals understand. The
+//! terminfo database describes the others, and the finer differences: an entry lists the
+//! capabilities of a terminal, which are booleans (`am`), numbers (`colors`) or strings
+//! (`cup`), under their short names.
+//!
+//! Both the legacy format and the format with 32-bit numbers of ncurses are read, with the
+//! extended (user-defined) capabilities.
+//!
+//! `TerminfoWriter` writes the sequences of termion, such as `cursor::Goto` or `style::Bold`, as
+//! the entry of the terminal spells them.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use termion::terminfo::Terminfo;
+//!
+//! fn main() {
+//!     let info = Terminfo::from_env().unwrap();
+//!     println!("{} has {:?} colors", info.name(), info.number("colors"));
+//! }
+//! ```
+
+mod names;
+mod output;
+mod param;
+
+pub use self::output::{TerminfoColor, TerminfoWriter, ToTerminfo};
+pub use self::param::{tparm, Param};
+
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use error::Error;
+
+/// The magic number of the legacy format, with 16-bit numbers.
+const MAGIC_LEGACY: u16 = 0o432;
+/// The magic number of the format with 32-bit numbers.
+const MAGIC_32BIT: u16 = 0o1036;
+
+/// The directories searched after `TERMINFO`, `~/.terminfo` and `TERMINFO_DIRS`.
+const DEFAULT_DIRS: &[&str] = &["/etc/terminfo", "/lib/terminfo", "/usr/share/terminfo",
+                                "/usr/lib/terminfo"];
+
+/// A terminfo entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Terminfo {
+    names: Vec<String>,
+    flags: HashSet<String>,
+    numbers: HashMap<String, i32>,
+    strings: HashMap<String, Vec<u8>>,
+}
+
+impl Terminfo {
+    /// Load the entry of the terminal named by `TERM`.
+    pub fn from_env() -> io::Result<Terminfo> {
+        let name = env::var("TERM").unwrap_or_default();
+        Terminfo::from_name(&name)
+    }
+
+    /// Load the entry of a terminal.
+    ///
+    /// It is searched in `TERMINFO`, then `~/.terminfo`, then the directories of `TERMINFO_DIRS`
+    /// (where an empty one stands for the system directories), and then the system directories.
+    pub fn from_name(name: &str) -> io::Result<Terminfo> {
+        Terminfo::from_path(find(&search_dirs(), name)?)
+    }
+
+    /// Load an entry from a file.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> io::Result<Terminfo> {
+        Terminfo::from_bytes(&fs::read(path)?)
+    }
+
+    /// Parse a compiled entry.
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Terminfo> {
+        parse(bytes).map_err(Into::into)
+    }
+
+    /// The main name of the terminal.
+    pub fn name(&self) -> &str {
+        &self.names[0]
+    }
+
+    /// The names of the terminal: the main name, the aliases, and the description last.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Check whether a boolean capability is set.
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.contains(name)
+    }
+
+    /// Get a numeric capability.
+    pub fn number(&self, name: &str) -> Option<i32> {
+        self.numbers.get(name).cloned()
+    }
+
+    /// Get a string capability.
+    pub fn string(&self, name: &str) -> Option<&[u8]> {
+        self.strings.get(name).map(|s| &s[..])
+    }
+
+    /// Expand a string capability with parameters. See `tparm`.
+    pub fn expand(&self, name: &str, params: &[Param]) -> io::Result<Vec<u8>> {
+        match self.string(name) {
+            Some(cap) => Ok(tparm(cap, params)),
+            None => Err(Error::MissingCapability { name: name.to_owned() }.into()),
+        }
+    }
+
+    /// The names of the boolean capabilities which are set.
+    pub fn flags(&self) -> impl Iterator<Item = &str> {
+        self.flags.iter().map(|name| &name[..])
+    }
+
+    /// The numeric capabilities.
+    pub fn numbers(&self) -> impl Iterator<Item = (&str, i32)> {
+        self.numbers.iter().map(|(name, &n)| (&name[..], n))
+    }
+
+    /// The string capabilities.
+    pub fn strings(&self) -> impl Iterator<Item = (&str, &[u8])> {
+        self.strings.iter().map(|(name, s)| (&name[..], &s[..]))
+    }
+}
+
+/// The directories to search, in order.
+fn search_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    if let Some(dir) = env::var_os("TERMINFO") {
+        dirs.push(PathBuf::from(dir));
+    }
+    if let Some(home) = env::var_os("HOME") {
+        dirs.push(Path::new(&home).join(".terminfo"));
+    }
+    if let Some(list) = env::var_os("TERMINFO_DIRS") {
+        for dir in env::split_paths(&list) {
+            if dir.as_os_str().is_empty() {
+                dirs.extend(DEFAULT_DIRS.iter().map(PathBuf::from));
+            } else {
+                dirs.push(dir);
+            }
+        }
+    }
+    dirs.extend(DEFAULT_DIRS.iter().map(PathBuf::from));
+
+    dirs
+}
+
+/// Find the file of an entry in the given directories.
+fn find(dirs: &[PathBuf], name: &str) -> io::Result<PathBuf> {
+    let first = match name.chars().next() {
+        Some(first) if !name.contains('/') && name != "." && name != ".." => first,
+        _ => return Err(Error::UnknownTerminal { name: name.to_owned() }.into()),
+    };
+
+    for dir in dirs {
+        // The entries are sorted by their first letter, or by its hexadecimal code on systems
+        // with case-insensitive file names.
+        for sub in &[first.to_string(), format!("{:x}", first as u32)] {
+            let path = dir.join(sub).join(name);
+            if path.is_file() {
+                return Ok(path);
+            }
+        }
+    }
+
+    Err(Error::UnknownTerminal { name: name.to_owned() }.into())
+}
+
+/// Reads the sections of a compiled entry.
+struct Input<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Input<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let bytes = self.bytes.get(self.pos..self.pos + len).ok_or_else(|| invalid("truncated"))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    /// Skip the padding byte which aligns the sections on even offsets.
+    fn align(&mut self) {
+        self.pos += self.pos % 2;
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    /// Read a little-endian signed number of 2 or 4 bytes.
+    fn number(&mut self, size: usize) -> Result<i32, Error> {
+        let bytes = self.take(size)?;
+        Ok(match size {
+            2 => i32::from(i16::from_le_bytes([bytes[0], bytes[1]])),
+            _ => i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        })
+    }
+
+    fn numbers(&mut self, count: usize, size: usize) -> Result<Vec<i32>, Error> {
+        (0..count).map(|_| self.number(size)).collect()
+    }
+
+    /// Read the size of a section.
+    fn count(&mut self) -> Result<usize, Error> {
+        match self.number(2)? {
+            n if n >= 0 => Ok(n as usize),
+            _ => Err(invalid("negative section size")),
+        }
+    }
+}
+
+fn invalid(reason: &str) -> Error {
+    Error::InvalidTerminfo { reason: reason.to_owned() }
+}
+
+/// Get the NUL-terminated string at `offset` in `table`.
+fn string_at(table: &[u8], offset: i32) -> Result<&[u8], Error> {
+    let s = table.get(offset as usize..).ok_or_else(|| invalid("string out of bounds"))?;
+    let len = s.iter().position(|&b| b == 0).ok_or_else(|| invalid("unterminated string"))?;
+    Ok(&s[..len])
+}
+
+fn parse(bytes: &[u8]) -> Result<Terminfo, Error> {
+    let mut input = Input { bytes, pos: 0 };
+
+    let number_size = match input.number(2)? as u16 {
+        MAGIC_LEGACY => 2,
+        MAGIC_32BIT => 4,
+        _ => return Err(invalid("unknown magic number")),
+    };
+    let names_size = input.count()?;
+    let flag_count = input.count()?;
+    let number_count = input.count()?;
+    let string_count = input.count()?;
+    let table_size = input.count()?;
+
+    let names = input.take(names_size)?;
+    let names = names.split(|&b| b == 0).next().unwrap_or(names);
+    let names: Vec<String> = String::from_utf8_lossy(names).split('|').map(String::from).collect();
+    if names[0].is_empty() {
+        return Err(invalid("no name"));
+    }
+
+    let mut info = Terminfo {
+        names,
+        flags: HashSet::new(),
+        numbers: HashMap::new(),
+        strings: HashMap::new(),
+    };
+
+    // Capabilities unknown to this version of the database are skipped, as are the absent (-1)
+    // and cancelled (-2) ones.
+    let flags = input.take(flag_count)?;
+    input.align();
+    let numbers = input.numbers(number_count, number_size)?;
+    let offsets = input.numbers(string_count, 2)?;
+    let table = input.take(table_size)?;
+
+    for (&name, &flag) in names::BOOLEANS.iter().zip(flags) {
+        if flag == 1 {
+            info.flags.insert(name.to_owned());
+        }
+    }
+    for (&name, &n) in names::NUMBERS.iter().zip(&numbers) {
+        if n >= 0 {
+            info.numbers.insert(name.to_owned(), n);
+        }
+    }
+    for (&name, &offset) in names::STRINGS.iter().zip(&offsets) {
+        if offset >= 0 {
+            info.strings.insert(name.to_owned(), string_at(table, offset)?.to_vec());
+        }
+    }
+
+    input.align();
+    if !input.is_empty() {
+        parse_extended(&mut input, number_size, &mut info)?;
+    }
+
+    Ok(info)
+}
+
+/// Parse the extended capabilities, which follow the standard ones with their names.
+fn parse_extended(input: &mut Input, number_size: usize, info: &mut Terminfo) -> Result<(), Error> {
+    let flag_count = input.count()?;
+    let number_count = input.count()?;
+    let string_count = input.count()?;
+    let _item_count = input.count()?;
+    let table_size = input.count()?;
+
+    let flags = input.take(flag_count)?;
+    input.align();
+    let numbers = input.numbers(number_count, number_size)?;
+    let offsets = input.numbers(string_count, 2)?;
+    let name_offsets = input.numbers(flag_count + number_count + string_count, 2)?;
+    let table = input.take(table_size)?;
+
+    // The names follow the values of the strings.
+    let mut names_start = 0;
+    for &offset in offsets.iter().filter(|&&offset| offset >= 0) {
+        let end = offset as usize + string_at(table, offset)?.len() + 1;
+        names_start = names_start.max(end);
+    }
+    let names = &table[names_start..];
+    let name = |i: usize| -> Result<String, Error> {
+        Ok(String::from_utf8_lossy(string_at(names, name_offsets[i])?).into_owned())
+    };
+
+    for (i, &flag) in flags.iter().enumerate() {
+        if flag == 1 {
+            info.flags.insert(name(i)?);
+        }
+    }
+    for (i, &n) in numbers.iter().enumerate() {
+        if n >= 0 {
+            info.numbers.insert(name(flag_count + i)?, n);
+        }
+    }
+    for (i, &offset) in offsets.iter().enumerate() {
+        if offset >= 0 {
+            let value = string_at(table, offset)?.to_vec();
+            info.strings.insert(name(flag_count + number_count + i)?, value);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn fixtures() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/terminfo")
+    }
+
+    fn fixture(name: &str) -> Terminfo {
+        Terminfo::from_path(find(&[fixtures()], name).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_legacy() {
+        let info = fixture("vt100");
+
+        assert_eq!(info.name(), "vt100");
+        assert!(info.names().len() > 1);
+        assert!(info.flag("am"));
+        assert!(!info.flag("bce"));
+        assert_eq!(info.number("cols"), Some(80));
+        assert_eq!(info.number("colors"), None);
+        assert_eq!(info.string("cup"), Some(&b"\x1B[%i%p1%d;%p2%dH$<5>"[..]));
+        assert_eq!(info.string("setaf"), None);
+
+        // Aliases are links to the file of the entry, whose primary name is the first one.
+        let alias = fixture("vt100-am");
+        assert_eq!(alias.name(), "vt100");
+        assert!(alias.names().contains(&"vt100-am".to_owned()));
+    }
+
+    #[test]
+    fn test_legacy_extended() {
+        let info = fixture("termion-test");
+
+        assert_eq!(info.names(), ["termion-test", "terminfo fixture for termion"]);
+        assert!(info.flag("xenl"));
+        assert_eq!(info.number("colors"), Some(8));
+        assert_eq!(info.string("bold"), Some(&b"\x1B[1m"[..]));
+        // Cancelled.
+        assert_eq!(info.string("smcup"), None);
+        assert!(!info.flag("XT"));
+
+        assert!(info.flag("AX"));
+        assert_eq!(info.number("U8"), Some(1));
+        assert_eq!(info.string("Ms"), Some(&b"\x1B]52;%p1%s;%p2%s\x07"[..]));
+    }
+
+    #[test]
+    fn test_32bit() {
+        let info = fixture("xterm-256color");
+
+        assert_eq!(info.name(), "xterm-256color");
+        assert_eq!(info.number("colors"), Some(256));
+        assert_eq!(info.number("pairs"), Some(65536));
+        assert_eq!(info.string("clear"), Some(&b"\x1B[H\x1B[2J"[..]));
+        assert!(info.flag("XT"));
+        assert_eq!(info.string("kUP5"), Some(&b"\x1B[1;5A"[..]));
+        assert!(info.strings().any(|(name, _)| name == "Ms"));
+    }
+
+    #[test]
+    fn test_errors() {
+        match Error::from(Terminfo::from_bytes(b"\x1A\x01\x10").unwrap_err()) {
+            Error::InvalidTerminfo { .. } => {}
+            err => panic!("unexpected error: {:?}", err),
+        }
+        match Error::from(Terminfo::from_bytes(b"\x00\x00\x00\x00").unwrap_err()) {
+            Error::InvalidTerminfo { reason } => assert_eq!(reason, "unknown magic number"),
+            err => panic!("unexpected error: {:?}", err),
+        }
+        match Error::from(find(&[fixtures()], "no-such-terminal").unwrap_err()) {
+            Error::UnknownTerminal { name } => assert_eq!(name, "no-such-terminal"),
+            err => panic!("unexpected error: {:?}", err),
+        }
+        assert!(find(&[fixtures()], "../t/termion-test").is_err());
+    }
+}
@@ -0,0 +1,455 @@
+//! Writing the sequences of termion through terminfo.
+
+use std::io::{self, Write};
+use std::iter;
+use std::thread;
+use std::time::Duration;
+
+use clear;
+use color::{self, Bg, Fg};
+use cursor;
+use error::Error;
+use screen;
+use style;
+use super::{Param, Terminfo};
+
+/// Sequences which can be looked up in terminfo.
+pub trait ToTerminfo {
+    /// Write the sequence as the terminal described by `info` spells it.
+    ///
+    /// This fails with `Error::MissingCapability` if the terminal cannot do it.
+    fn write_terminfo(&self, info: &Terminfo, out: &mut Vec<u8>) -> io::Result<()>;
+}
+
+/// Colors which can be looked up in terminfo.
+pub trait TerminfoColor {
+    /// The number of the color for `setaf` and `setab`, or `None` for the default color.
+    fn terminfo_color(&self, info: &Terminfo) -> io::Result<Option<i32>>;
+}
+
+macro_rules! derive_terminfo_sequence {
+    ($($ty:ty => $name:expr),* $(,)*) => {
+        $(
+            impl ToTerminfo for $ty {
+                fn write_terminfo(&self, info: &Terminfo, out: &mut Vec<u8>) -> io::Result<()> {
+                    out.extend(info.expand($name, &[])?);
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+derive_terminfo_sequence! {
+    clear::All => "clear",
+    clear::AfterCursor => "ed",
+    clear::UntilNewline => "el",
+    cursor::Hide => "civis",
+    cursor::Show => "cnorm",
+    cursor::Save => "sc",
+    cursor::Restore => "rc",
+    screen::ToAlternateScreen => "smcup",
+    screen::ToMainScreen => "rmcup",
+    style::Reset => "sgr0",
+    style::Bold => "bold",
+    style::Faint => "dim",
+    style::Italic => "sitm",
+    style::NoItalic => "ritm",
+    style::Underline => "smul",
+    style::NoUnderline => "rmul",
+    style::Blink => "blink",
+    style::Invert => "rev",
+    style::CrossedOut => "smxx",
+    style::NoCrossedOut => "rmxx",
+}
+
+impl ToTerminfo for cursor::Goto {
+    fn write_terminfo(&self, info: &Terminfo, out: &mut Vec<u8>) -> io::Result<()> {
+        // `cup` takes the zero-based line, then column.
+        let params = [Param::Number(i32::from(self.1) - 1), Param::Number(i32::from(self.0) - 1)];
+        out.extend(info.expand("cup", &params)?);
+        Ok(())
+    }
+}
+
+/// Move the cursor with the capability taking a count, or by repeating the single step.
+fn move_cursor(info: &Terminfo, out: &mut Vec<u8>, name: &str, count: u16) -> io::Result<()> {
+    if info.string(name).is_some() {
+        out.extend(info.expand(name, &[Param::Number(i32::from(count))])?);
+    } else {
+        let step = info.expand(&format!("{}1", name), &[])?;
+        for _ in 0..count {
+            out.extend(&step);
+        }
+    }
+    Ok(())
+}
+
+macro_rules! derive_terminfo_movement {
+    ($($ty:ident => $name:expr),*) => {
+        $(
+            impl ToTerminfo for cursor::$ty {
+                fn write_terminfo(&self, info: &Terminfo, out: &mut Vec<u8>) -> io::Result<()> {
+                    move_cursor(info, out, $name, self.0)
+                }
+            }
+        )*
+    };
+}
+
+derive_terminfo_movement!(Left => "cub", Right => "cuf", Up => "cuu", Down => "cud");
+
+macro_rules! derive_terminfo_color {
+    ($($ty:ident = $n:expr),*) => {
+        $(
+            impl TerminfoColor for color::$ty {
+                fn terminfo_color(&self, _info: &Terminfo) -> io::Result<Option<i32>> {
+                    Ok(Some($n))
+                }
+            }
+        )*
+    };
+}
+
+derive_terminfo_color!(Black = 0, Red = 1, Green = 2, Yellow = 3, Blue = 4, Magenta = 5, Cyan = 6,
+                       White = 7, LightBlack = 8, LightRed = 9, LightGreen = 10,
+                       LightYellow = 11, LightBlue = 12, LightMagenta = 13, LightCyan = 14,
+                       LightWhite = 15);
+
+impl TerminfoColor for color::AnsiValue {
+    fn terminfo_color(&self, _info: &Terminfo) -> io::Result<Option<i32>> {
+        Ok(Some(i32::from(self.0)))
+    }
+}
+
+impl TerminfoColor for color::Rgb {
+    /// Direct color terminals, such as `xterm-direct`, take the color as `0xRRGGBB`.
+    fn terminfo_color(&self, info: &Terminfo) -> io::Result<Option<i32>> {
+        if info.flag("RGB") || info.number("colors") >= Some(1 << 24) {
+            Ok(Some(i32::from(self.0) << 16 | i32::from(self.1) << 8 | i32::from(self.2)))
+        } else {
+            Err(Error::MissingCapability { name: "RGB".to_owned() }.into())
+        }
+    }
+}
+
+impl TerminfoColor for color::Reset {
+    fn terminfo_color(&self, _info: &Terminfo) -> io::Result<Option<i32>> {
+        Ok(None)
+    }
+}
+
+/// Write a color with `set` (`setaf` or `setab`), or reset both colors with `op`.
+fn write_color<C>(color: &C, info: &Terminfo, out: &mut Vec<u8>, set: &str) -> io::Result<()>
+    where C: TerminfoColor
+{
+    match color.terminfo_color(info)? {
+        Some(n) => out.extend(info.expand(set, &[Param::Number(n)])?),
+        None => out.extend(info.expand("op", &[])?),
+    }
+    Ok(())
+}
+
+impl<C: color::Color + TerminfoColor> ToTerminfo for Fg<C> {
+    /// `Fg(Reset)` resets the background color too, as there is no capability for either alone.
+    fn write_terminfo(&self, info: &Terminfo, out: &mut Vec<u8>) -> io::Result<()> {
+        write_color(&self.0, info, out, "setaf")
+    }
+}
+
+impl<C: color::Color + TerminfoColor> ToTerminfo for Bg<C> {
+    /// `Bg(Reset)` resets the foreground color too, as there is no capability for either alone.
+    fn write_terminfo(&self, info: &Terminfo, out: &mut Vec<u8>) -> io::Result<()> {
+        write_color(&self.0, info, out, "setab")
+    }
+}
+
+/// A part of an expanded capability.
+#[derive(Debug, PartialEq)]
+enum Chunk<'a> {
+    /// Bytes to write as they are.
+    Text(&'a [u8]),
+    /// A delay, such as `$<5>` or `$<2*/>`, in milliseconds.
+    ///
+    /// The delay is mandatory when followed by `/`. A `*` makes it proportional to the number of
+    /// lines affected, which is always taken to be one.
+    Padding { millis: f64, mandatory: bool },
+}
+
+/// Split an expanded capability into its text and its padding.
+fn split_padding(seq: &[u8]) -> Vec<Chunk<'_>> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < seq.len() {
+        if seq[i..].starts_with(b"$<") {
+            let spec = &seq[i + 2..];
+            let digits = spec.iter().take_while(|&&b| b.is_ascii_digit() || b == b'.').count();
+            let flags = spec[digits..].iter().take_while(|&&b| b == b'*' || b == b'/').count();
+            let millis = String::from_utf8_lossy(&spec[..digits]).parse::<f64>();
+            if let (Ok(millis), Some(&b'>')) = (millis, spec.get(digits + flags)) {
+                if start < i {
+                    chunks.push(Chunk::Text(&seq[start..i]));
+                }
+                chunks.push(Chunk::Padding {
+                    millis,
+                    mandatory: spec[digits..digits + flags].contains(&b'/'),
+                });
+                i += digits + flags + 3;
+                start = i;
+                continue;
+            }
+        }
+        i += 1;
+    }
+    if start < seq.len() {
+        chunks.push(Chunk::Text(&seq[start..]));
+    }
+
+    chunks
+}
+
+/// How to wait for the terminal.
+#[derive(Debug, PartialEq)]
+enum Delay {
+    /// Write that many pad characters.
+    Pad(usize),
+    /// Flush the output, and sleep.
+    Sleep(Duration),
+}
+
+/// A writer which writes the sequences of termion as the terminal spells them.
+///
+/// Everything else is written as is.
+///
+/// The capabilities of old terminals ask for padding, to give them the time to execute the
+/// sequences. It is only written once the baud rate of the line is given with `set_baud_rate`,
+/// as `tputs` does: pad characters are sent when it is at least the `pb` capability, unless the
+/// terminal has XON/XOFF flow control (`xon`), and the writer sleeps instead for terminals
+/// without pad characters (`npc`). Mandatory padding, such as `$<5/>`, is always applied: the
+/// writer sleeps for it when there is no baud rate.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use termion::{clear, cursor, style};
+/// use termion::terminfo::TerminfoWriter;
+/// use std::io::{Write, stdout};
+///
+/// fn main() {
+///     let mut out = TerminfoWriter::from_env(stdout()).unwrap();
+///
+///     out.emit(clear::All).unwrap();
+///     out.emit(cursor::Goto(5, 3)).unwrap();
+///     out.emit(style::Bold).unwrap();
+///     write!(out, "Stuff").unwrap();
+///     out.emit(style::Reset).unwrap();
+///     out.flush().unwrap();
+/// }
+/// ```
+pub struct TerminfoWriter<W: Write> {
+    output: W,
+    info: Terminfo,
+    buf: Vec<u8>,
+    /// The baud rate of the line, if padding is needed.
+    baud: Option<u32>,
+    /// Whether even mandatory padding is left out.
+    strip_padding: bool,
+}
+
+impl<W: Write> TerminfoWriter<W> {
+    /// Wrap a writer, with the entry of its terminal.
+    pub fn new(output: W, info: Terminfo) -> TerminfoWriter<W> {
+        TerminfoWriter {
+            output,
+            info,
+            buf: Vec::new(),
+            baud: None,
+            strip_padding: false,
+        }
+    }
+
+    /// Wrap a writer, with the entry of the terminal named by `TERM`.
+    pub fn from_env(output: W) -> io::Result<TerminfoWriter<W>> {
+        Ok(TerminfoWriter::new(output, Terminfo::from_env()?))
+    }
+
+    /// The entry of the terminal.
+    pub fn info(&self) -> &Terminfo {
+        &self.info
+    }
+
+    /// Set the baud rate of the line, to write the padding the terminal needs at that speed.
+    ///
+    /// It can be read from the terminal with `Termios::output_speed`. Without a baud rate, only
+    /// mandatory padding is applied, by sleeping.
+    pub fn set_baud_rate(&mut self, baud: Option<u32>) {
+        self.baud = baud;
+    }
+
+    /// Set whether all the padding is left out, even the mandatory padding.
+    pub fn set_strip_padding(&mut self, strip: bool) {
+        self.strip_padding = strip;
+    }
+
+    /// Write a sequence.
+    ///
+    /// This fails with `Error::MissingCapability`, writing nothing, if the terminal cannot do it.
+    pub fn emit<T: ToTerminfo>(&mut self, seq: T) -> io::Result<()> {
+        self.buf.clear();
+        seq.write_terminfo(&self.info, &mut self.buf)?;
+
+        let mut out = Vec::with_capacity(self.buf.len());
+        for chunk in split_padding(&self.buf) {
+            let (millis, mandatory) = match chunk {
+                Chunk::Text(text) => {
+                    out.extend_from_slice(text);
+                    continue;
+                }
+                Chunk::Padding { millis, mandatory } => (millis, mandatory),
+            };
+            match self.delay(millis, mandatory) {
+                Some(Delay::Pad(n)) => {
+                    let pad = self.info.string("pad").and_then(|pad| pad.first().cloned());
+                    out.extend(iter::repeat_n(pad.unwrap_or(0), n));
+                }
+                Some(Delay::Sleep(duration)) => {
+                    self.output.write_all(&out)?;
+                    self.output.flush()?;
+                    out.clear();
+                    thread::sleep(duration);
+                }
+                None => {}
+            }
+        }
+        self.output.write_all(&out)
+    }
+
+    /// How to wait for the given padding, if at all.
+    fn delay(&self, millis: f64, mandatory: bool) -> Option<Delay> {
+        if self.strip_padding {
+            return None;
+        }
+
+        let needed = match self.baud {
+            _ if mandatory => true,
+            Some(baud) if !self.info.flag("xon") => {
+                self.info.number("pb").is_none_or(|pb| i64::from(baud) >= i64::from(pb))
+            }
+            _ => false,
+        };
+        match self.baud {
+            _ if !needed => None,
+            // A character takes about 9 bits on the line, as `tputs` counts them.
+            Some(baud) if !self.info.flag("npc") => {
+                Some(Delay::Pad((millis * f64::from(baud) / 9000.0) as usize))
+            }
+            // Without pad characters, or a baud rate to count them in, wait instead.
+            _ => Some(Delay::Sleep(Duration::from_micros((millis * 1000.0) as u64))),
+        }
+    }
+
+    /// Get the inner writer back.
+    pub fn into_inner(self) -> W {
+        self.output
+    }
+}
+
+impl<W: Write> Write for TerminfoWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Instant;
+
+    fn writer(name: &str) -> TerminfoWriter<Vec<u8>> {
+        let path = format!("{}/tests/terminfo/{}/{}",
+                           env!("CARGO_MANIFEST_DIR"),
+                           &name[..1],
+                           name);
+        TerminfoWriter::new(Vec::new(), Terminfo::from_path(path).unwrap())
+    }
+
+    #[test]
+    fn test_emit() {
+        let mut out = writer("xterm-256color");
+        out.emit(cursor::Goto(10, 5)).unwrap();
+        out.emit(clear::All).unwrap();
+        out.emit(style::Bold).unwrap();
+        out.emit(Fg(color::Red)).unwrap();
+        out.emit(Bg(color::AnsiValue(100))).unwrap();
+        out.emit(Fg(color::Reset)).unwrap();
+        out.emit(cursor::Left(3)).unwrap();
+        assert_eq!(out.into_inner(),
+                   &b"\x1B[5;10H\x1B[H\x1B[2J\x1B[1m\x1B[31m\x1B[48;5;100m\x1B[39;49m\x1B[3D"[..]);
+    }
+
+    #[test]
+    fn test_old_terminal() {
+        let mut out = writer("vt100");
+        // The terminal has flow control, so the padding is removed.
+        out.set_baud_rate(Some(9600));
+        out.emit(cursor::Goto(1, 1)).unwrap();
+        out.emit(style::Bold).unwrap();
+        assert_eq!(out.info().string("bold"), Some(&b"\x1B[1m$<2>"[..]));
+
+        match Error::from(out.emit(Fg(color::Red)).unwrap_err()) {
+            Error::MissingCapability { name } => assert_eq!(name, "setaf"),
+            err => panic!("unexpected error: {:?}", err),
+        }
+        match Error::from(out.emit(Fg(color::Rgb(1, 2, 3))).unwrap_err()) {
+            Error::MissingCapability { name } => assert_eq!(name, "RGB"),
+            err => panic!("unexpected error: {:?}", err),
+        }
+        assert_eq!(out.into_inner(), &b"\x1B[1;1H\x1B[1m"[..]);
+    }
+
+    #[test]
+    fn test_step_movement() {
+        let mut out = writer("termion-test");
+        out.emit(Fg(color::Blue)).unwrap();
+        out.emit(cursor::Up(2)).unwrap();
+        assert!(out.emit(cursor::Down(2)).is_err());
+        assert_eq!(out.into_inner(), b"\x1B[34m\x1B[A\x1B[A");
+    }
+
+    #[test]
+    fn test_padding() {
+        let mut out = writer("termion-test");
+        out.emit(clear::UntilNewline).unwrap();
+        // Without a baud rate, the writer sleeps for the mandatory padding.
+        let start = Instant::now();
+        out.emit(clear::AfterCursor).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(9));
+        assert_eq!(out.delay(9.0, true), Some(Delay::Sleep(Duration::from_millis(9))));
+        // Below `pb`, only the mandatory padding is written.
+        out.set_baud_rate(Some(600));
+        out.emit(clear::UntilNewline).unwrap();
+        out.set_baud_rate(Some(9600));
+        out.emit(clear::UntilNewline).unwrap();
+        out.emit(clear::AfterCursor).unwrap();
+        out.set_strip_padding(true);
+        out.emit(clear::AfterCursor).unwrap();
+        assert_eq!(out.into_inner(),
+                   &b"\x1B[K\x1B[J\x1B[K\x1B[K\0\0\0\0\0\0\0\0\0\0\x1B[J\0\0\0\0\0\0\0\0\0\x1B[J"[..]);
+    }
+
+    #[test]
+    fn test_split_padding() {
+        assert_eq!(split_padding(b"a$<5>b$<2.5*/>"),
+                   [Chunk::Text(b"a"),
+                    Chunk::Padding { millis: 5.0, mandatory: false },
+                    Chunk::Text(b"b"),
+                    Chunk::Padding { millis: 2.5, mandatory: true }]);
+        assert_eq!(split_padding(b"$<x>$<>$<"), [Chunk::Text(b"$<x>$<>$<")]);
+    }
+}
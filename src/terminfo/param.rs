@@ -0,0 +1,411 @@
+//! The parameterized strings of terminfo.
+
+/// A parameter given to a string capability.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Param {
+    /// A number, such as a line or a color.
+    Number(i32),
+    /// A string, such as a label.
+    String(Vec<u8>),
+}
+
+impl Default for Param {
+    fn default() -> Param {
+        Param::Number(0)
+    }
+}
+
+impl From<i32> for Param {
+    fn from(n: i32) -> Param {
+        Param::Number(n)
+    }
+}
+
+impl<'a> From<&'a str> for Param {
+    fn from(s: &'a str) -> Param {
+        Param::String(s.as_bytes().to_vec())
+    }
+}
+
+impl Param {
+    fn number(&self) -> i32 {
+        match *self {
+            Param::Number(n) => n,
+            Param::String(_) => 0,
+        }
+    }
+}
+
+/// Expand a parameterized string capability, like `tparm` of ncurses.
+///
+/// The whole language is understood: `%p1`, `%d`, `%i`, the conditionals, the arithmetic and
+/// the variables, and the `printf` formats. The missing parameters are zero. The static variables
+/// (`%PA` to `%PZ`) do not outlive the call. The padding (`$<5>`) is kept.
+///
+/// Malformed sequences are written out as they are, which keeps the strings which are not
+/// parameterized, such as `acsc`, intact.
+pub fn tparm(cap: &[u8], params: &[Param]) -> Vec<u8> {
+    let mut expansion = Expansion {
+        params: Default::default(),
+        stack: Vec::new(),
+        vars: vec![Param::default(); 52],
+    };
+    for (slot, param) in expansion.params.iter_mut().zip(params) {
+        *slot = param.clone();
+    }
+
+    expansion.run(cap)
+}
+
+/// The state of an expansion.
+struct Expansion {
+    params: [Param; 9],
+    stack: Vec<Param>,
+    /// The dynamic variables, `a` to `z`, then the static ones, `A` to `Z`.
+    vars: Vec<Param>,
+}
+
+impl Expansion {
+    fn pop(&mut self) -> Param {
+        // An empty stack gives zeros, as in ncurses.
+        self.stack.pop().unwrap_or_default()
+    }
+
+    fn pop_number(&mut self) -> i32 {
+        self.pop().number()
+    }
+
+    fn push_number(&mut self, n: i32) {
+        self.stack.push(Param::Number(n));
+    }
+
+    fn run(&mut self, cap: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut i = 0;
+
+        while i < cap.len() {
+            if cap[i] != b'%' {
+                out.push(cap[i]);
+                i += 1;
+                continue;
+            }
+
+            match self.op(cap, i + 1, &mut out) {
+                Some(next) => i = next,
+                None => {
+                    out.push(b'%');
+                    i += 1;
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Run the operation at `i`, after a `%`, and return the position after it, or `None`
+    /// without any effect if it is malformed.
+    fn op(&mut self, cap: &[u8], mut i: usize, out: &mut Vec<u8>) -> Option<usize> {
+        let op = *cap.get(i)?;
+        i += 1;
+        match op {
+            b'%' => out.push(b'%'),
+            b'c' => out.push(self.pop_number() as u8),
+            b'd' | b'o' | b'x' | b'X' | b's' => {
+                let value = self.pop();
+                format(out, &Spec::new(op), &value);
+            }
+            b':' | b'#' | b' ' | b'.' | b'0'..=b'9' => {
+                let (spec, len) = Spec::parse(&cap[i - 1..])?;
+                i += len - 1;
+                let value = self.pop();
+                format(out, &spec, &value);
+            }
+            b'p' => {
+                let n = match cap.get(i) {
+                    Some(&d @ b'1'..=b'9') => (d - b'1') as usize,
+                    _ => return None,
+                };
+                i += 1;
+                let param = self.params[n].clone();
+                self.stack.push(param);
+            }
+            b'P' | b'g' => {
+                let var = match cap.get(i) {
+                    Some(&c @ b'a'..=b'z') => (c - b'a') as usize,
+                    Some(&c @ b'A'..=b'Z') => 26 + (c - b'A') as usize,
+                    _ => return None,
+                };
+                i += 1;
+                if op == b'P' {
+                    self.vars[var] = self.pop();
+                } else {
+                    let value = self.vars[var].clone();
+                    self.stack.push(value);
+                }
+            }
+            b'\'' => {
+                match (cap.get(i), cap.get(i + 1)) {
+                    (Some(&c), Some(&b'\'')) => self.push_number(i32::from(c)),
+                    _ => return None,
+                }
+                i += 2;
+            }
+            b'{' => {
+                let len = cap[i..]
+                    .iter()
+                    .position(|&b| b == b'}')?;
+                let n = String::from_utf8_lossy(&cap[i..i + len])
+                    .parse()
+                    .ok()?;
+                self.push_number(n);
+                i += len + 1;
+            }
+            b'l' => {
+                let len = match self.pop() {
+                    Param::String(s) => s.len() as i32,
+                    Param::Number(_) => 0,
+                };
+                self.push_number(len);
+            }
+            b'+' | b'-' | b'*' | b'/' | b'm' | b'&' | b'|' | b'^' | b'=' | b'<' | b'>' |
+            b'A' | b'O' => {
+                let y = self.pop_number();
+                let x = self.pop_number();
+                let n = match op {
+                    b'+' => x.wrapping_add(y),
+                    b'-' => x.wrapping_sub(y),
+                    b'*' => x.wrapping_mul(y),
+                    b'/' => x.checked_div(y).unwrap_or(0),
+                    b'm' => x.checked_rem(y).unwrap_or(0),
+                    b'&' => x & y,
+                    b'|' => x | y,
+                    b'^' => x ^ y,
+                    b'=' => (x == y) as i32,
+                    b'<' => (x < y) as i32,
+                    b'>' => (x > y) as i32,
+                    b'A' => (x != 0 && y != 0) as i32,
+                    _ => (x != 0 || y != 0) as i32,
+                };
+                self.push_number(n);
+            }
+            b'!' => {
+                let x = self.pop_number();
+                self.push_number((x == 0) as i32);
+            }
+            b'~' => {
+                let x = self.pop_number();
+                self.push_number(!x);
+            }
+            b'i' => {
+                for param in self.params.iter_mut().take(2) {
+                    if let Param::Number(ref mut n) = *param {
+                        *n += 1;
+                    }
+                }
+            }
+            b'?' | b';' => {}
+            // The condition is false: go to the else part.
+            b't' => {
+                if self.pop_number() == 0 {
+                    i = skip(cap, i, true);
+                }
+            }
+            // The then part is over: go past the end of the conditional.
+            b'e' => i = skip(cap, i, false),
+            _ => return None,
+        }
+
+        Some(i)
+    }
+}
+
+/// Skip to the end of the conditional starting before `i`, or to its next else part if
+/// `to_else` is set, and return the position after it.
+fn skip(cap: &[u8], mut i: usize, to_else: bool) -> usize {
+    let mut depth = 0;
+
+    while i < cap.len() {
+        if cap[i] != b'%' {
+            i += 1;
+            continue;
+        }
+
+        let op = cap.get(i + 1).cloned();
+        i += 2;
+        match op {
+            Some(b'?') => depth += 1,
+            Some(b';') if depth == 0 => return i,
+            Some(b';') => depth -= 1,
+            Some(b'e') if depth == 0 && to_else => return i,
+            // A character constant may be `%`.
+            Some(b'\'') => i += 2,
+            _ => {}
+        }
+    }
+
+    i
+}
+
+/// A `printf` format.
+#[derive(Default)]
+struct Spec {
+    left: bool,
+    plus: bool,
+    space: bool,
+    alternate: bool,
+    zero: bool,
+    width: usize,
+    precision: Option<usize>,
+    conversion: u8,
+}
+
+impl Spec {
+    fn new(conversion: u8) -> Spec {
+        Spec {
+            conversion,
+            ..Spec::default()
+        }
+    }
+
+    /// Parse a format after its `%`, returning it with its length.
+    fn parse(s: &[u8]) -> Option<(Spec, usize)> {
+        let mut spec = Spec::default();
+        let mut i = 0;
+
+        // `%-` and `%+` are operators, so the flags may be set apart with a colon.
+        if s.first() == Some(&b':') {
+            i += 1;
+        }
+        loop {
+            match s.get(i) {
+                Some(&b'-') => spec.left = true,
+                Some(&b'+') => spec.plus = true,
+                Some(&b' ') => spec.space = true,
+                Some(&b'#') => spec.alternate = true,
+                Some(&b'0') => spec.zero = true,
+                _ => break,
+            }
+            i += 1;
+        }
+
+        let digits = |i: &mut usize| {
+            let mut n = 0;
+            while let Some(&d @ b'0'..=b'9') = s.get(*i) {
+                n = n * 10 + (d - b'0') as usize;
+                *i += 1;
+            }
+            n
+        };
+        spec.width = digits(&mut i);
+        if s.get(i) == Some(&b'.') {
+            i += 1;
+            spec.precision = Some(digits(&mut i));
+        }
+
+        match s.get(i) {
+            Some(&c @ b'd') | Some(&c @ b'o') | Some(&c @ b'x') | Some(&c @ b'X') |
+            Some(&c @ b's') => {
+                spec.conversion = c;
+                Some((spec, i + 1))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Write a value with a format.
+fn format(out: &mut Vec<u8>, spec: &Spec, value: &Param) {
+    let body = match (spec.conversion, value) {
+        (b's', Param::String(s)) => {
+            s[..spec.precision.map_or(s.len(), |p| p.min(s.len()))].to_vec()
+        }
+        (b's', &Param::Number(n)) => n.to_string().into_bytes(),
+        (conversion, value) => {
+            let n = value.number();
+            let mut digits = match conversion {
+                b'o' => format!("{:o}", n as u32),
+                b'x' => format!("{:x}", n as u32),
+                b'X' => format!("{:X}", n as u32),
+                _ => n.unsigned_abs().to_string(),
+            };
+            if let Some(precision) = spec.precision {
+                while digits.len() < precision {
+                    digits.insert(0, '0');
+                }
+            }
+
+            let prefix = match conversion {
+                b'd' if n < 0 => "-",
+                b'd' if spec.plus => "+",
+                b'd' if spec.space => " ",
+                b'o' if spec.alternate && !digits.starts_with('0') => "0",
+                b'x' if spec.alternate && n != 0 => "0x",
+                b'X' if spec.alternate && n != 0 => "0X",
+                _ => "",
+            };
+            if spec.zero && !spec.left && spec.precision.is_none() {
+                while prefix.len() + digits.len() < spec.width {
+                    digits.insert(0, '0');
+                }
+            }
+
+            format!("{}{}", prefix, digits).into_bytes()
+        }
+    };
+
+    let padding = vec![b' '; spec.width.saturating_sub(body.len())];
+    if spec.left {
+        out.extend(body);
+        out.extend(padding);
+    } else {
+        out.extend(padding);
+        out.extend(body);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn expand(cap: &str, params: &[Param]) -> String {
+        String::from_utf8(tparm(cap.as_bytes(), params)).unwrap()
+    }
+
+    #[test]
+    fn test_tparm() {
+        let cup = "\x1B[%i%p1%d;%p2%dH";
+        assert_eq!(expand(cup, &[4.into(), 9.into()]), "\x1B[5;10H");
+
+        let setaf = "\x1B[%?%p1%{8}%<%t3%p1%d%e%p1%{16}%<%t9%p1%{8}%-%d%e38;5;%p1%d%;m";
+        assert_eq!(expand(setaf, &[1.into()]), "\x1B[31m");
+        assert_eq!(expand(setaf, &[9.into()]), "\x1B[91m");
+        assert_eq!(expand(setaf, &[100.into()]), "\x1B[38;5;100m");
+
+        let direct = "\x1B[%?%p1%{8}%<%t3%p1%d%e38:2::%p1%{65536}%/%d:%p1%{256}%/%{255}%&%d:\
+                      %p1%{255}%&%d%;m";
+        assert_eq!(expand(direct, &[0x10_20_30.into()]), "\x1B[38:2::16:32:48m");
+
+        assert_eq!(expand("%p1%s=%p2%l%d", &["ab".into(), "xyz".into()]), "ab=3");
+        assert_eq!(expand("%'a'%c%{3}%Pb%gb%gb%*%d", &[]), "a9");
+        assert_eq!(expand("%p1%!%d%p1%~%d%{7}%{2}%m%d%%", &[0.into()]), "1-11%");
+        assert_eq!(expand("%?%p1%t%?%p2%tA%eB%;%eC%;", &[1.into(), 0.into()]), "B");
+        assert_eq!(expand("%?%p1%tA%e%p2%tB%eC%;", &[0.into(), 0.into()]), "C");
+    }
+
+    #[test]
+    fn test_formats() {
+        assert_eq!(expand("%p1%3d|%p1%:-3d|%p1%03d|%p1%:+d|%p1% d", &[5.into()]),
+                   "  5|5  |005|+5| 5");
+        assert_eq!(expand("%p1%x %p1%#X %p1%o %p1%.4d", &[255.into()]), "ff 0XFF 377 0255");
+        assert_eq!(expand("%p1%5.2s|", &["abc".into()]), "   ab|");
+        assert_eq!(expand("%p1%d$<5>", &[(-3).into()]), "-3$<5>");
+    }
+
+    #[test]
+    fn test_malformed() {
+        assert_eq!(expand("%p0%d", &[]), "%p00");
+        assert_eq!(expand("%{12", &[]), "%{12");
+        assert_eq!(expand("j%k4l<", &[]), "j%k4l<");
+        assert_eq!(expand("abc%", &[]), "abc%");
+    }
+}
@@ -0,0 +1,581 @@
+//! Terminal attributes.
+//!
+//! `Termios` gives safe access to the attributes of a terminal, as `tcgetattr(3)` and
+//! `tcsetattr(3)` do, with named flags, control characters and speeds which mean the same on
+//! every supported platform. The flags and control characters left unnamed here are kept as they
+//! are.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use termion::termios::{LocalFlags, Termios};
+//! use std::io::stdin;
+//! use std::os::unix::io::AsRawFd;
+//!
+//! fn main() {
+//!     let fd = stdin().as_raw_fd();
+//!     let prev = Termios::get(fd).unwrap();
+//!
+//!     let mut ios = prev;
+//!     ios.set_local_flags(ios.local_flags() - LocalFlags::ECHO);
+//!     ios.set(fd).unwrap();
+//!
+//!     // Read a password...
+//!
+//!     prev.set(fd).unwrap();
+//! }
+//! ```
+
+use std::fmt;
+use std::io;
+use std::ops;
+use std::os::unix::io::RawFd;
+
+use sys;
+use sys::attr::{self, Flag};
+
+/// The raw bits of the flags, `tcflag_t`.
+pub type FlagBits = Flag;
+
+macro_rules! flags {
+    ($(#[$attr:meta])* pub struct $name:ident {
+        $($(#[doc = $doc:expr])* $(#[cfg($cfg:meta)])* const $flag:ident;)*
+    }) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+        pub struct $name(Flag);
+
+        impl $name {
+            $(
+                $(#[doc = $doc])*
+                $(#[cfg($cfg)])*
+                pub const $flag: $name = $name(attr::$flag);
+            )*
+
+            /// No flags at all.
+            pub fn empty() -> $name {
+                $name(0)
+            }
+
+            /// Get the flags from their raw bits, as in `termios(3)`, keeping the bits which
+            /// have no name here.
+            ///
+            /// This is how the flags which are not named here, such as `IUTF8`, can be set.
+            pub fn from_bits_retain(bits: FlagBits) -> $name {
+                $name(bits)
+            }
+
+            /// The raw bits of the flags, as in `termios(3)`.
+            pub fn bits(self) -> FlagBits {
+                self.0
+            }
+
+            /// Check whether no flag is set.
+            pub fn is_empty(self) -> bool {
+                self.0 == 0
+            }
+
+            /// Check whether all the flags of `other` are set.
+            pub fn contains(self, other: $name) -> bool {
+                self.0 & other.0 == other.0
+            }
+
+            /// Check whether any of the flags of `other` is set.
+            pub fn intersects(self, other: $name) -> bool {
+                self.0 & other.0 != 0
+            }
+
+            /// Set the flags of `other`.
+            pub fn insert(&mut self, other: $name) {
+                self.0 |= other.0;
+            }
+
+            /// Clear the flags of `other`.
+            pub fn remove(&mut self, other: $name) {
+                self.0 &= !other.0;
+            }
+
+            /// Set or clear the flags of `other`.
+            pub fn set(&mut self, other: $name, value: bool) {
+                if value {
+                    self.insert(other);
+                } else {
+                    self.remove(other);
+                }
+            }
+        }
+
+        impl ops::BitOr for $name {
+            type Output = $name;
+
+            fn bitor(self, other: $name) -> $name {
+                $name(self.0 | other.0)
+            }
+        }
+
+        impl ops::BitAnd for $name {
+            type Output = $name;
+
+            fn bitand(self, other: $name) -> $name {
+                $name(self.0 & other.0)
+            }
+        }
+
+        impl ops::Sub for $name {
+            type Output = $name;
+
+            fn sub(self, other: $name) -> $name {
+                $name(self.0 & !other.0)
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                let mut rest = self.0;
+                let mut names = Vec::new();
+                $(
+                    $(#[cfg($cfg)])*
+                    if attr::$flag != 0 && self.0 & attr::$flag == attr::$flag {
+                        names.push(stringify!($flag).to_owned());
+                        rest &= !attr::$flag;
+                    }
+                )*
+                if rest != 0 || names.is_empty() {
+                    names.push(format!("{:#o}", rest));
+                }
+                write!(f, "{}({})", stringify!($name), names.join(" | "))
+            }
+        }
+    };
+}
+
+flags! {
+    /// The input flags, controlling how input is processed.
+    pub struct InputFlags {
+        /// Ignore break conditions.
+        const IGNBRK;
+        /// Flush the queues and send `SIGINT` on a break condition.
+        const BRKINT;
+        /// Ignore bytes with framing or parity errors.
+        const IGNPAR;
+        /// Mark bytes with parity errors with the `\xFF\0` prefix.
+        const PARMRK;
+        /// Check the parity of the input.
+        const INPCK;
+        /// Strip the eighth bit of every byte.
+        const ISTRIP;
+        /// Translate NL to CR.
+        const INLCR;
+        /// Ignore CR.
+        const IGNCR;
+        /// Translate CR to NL.
+        const ICRNL;
+        /// Let the STOP and START characters pause and resume the output.
+        const IXON;
+        /// Send STOP and START characters when the input queue fills up and empties.
+        const IXOFF;
+    }
+}
+
+flags! {
+    /// The output flags, controlling how output is processed.
+    pub struct OutputFlags {
+        /// Process the output at all. The other output flags only matter with this one set.
+        const OPOST;
+        /// Translate NL to CR-NL.
+        const ONLCR;
+        /// Translate CR to NL.
+        const OCRNL;
+        /// Do not output CR at the start of a line.
+        const ONOCR;
+        /// Do not output CR, as NL does its job.
+        const ONLRET;
+    }
+}
+
+flags! {
+    /// The control flags, controlling the hardware line.
+    ///
+    /// The character size is set through the `CSIZE` bits, to one of `CS5`, `CS6`, `CS7` or
+    /// `CS8`.
+    pub struct ControlFlags {
+        /// Use two stop bits rather than one.
+        const CSTOPB;
+        /// Enable the receiver.
+        const CREAD;
+        /// Generate and check parity.
+        const PARENB;
+        /// Use odd parity rather than even parity.
+        const PARODD;
+        /// Hang up when the last file descriptor to the device is closed.
+        const HUPCL;
+        /// Ignore the modem control lines.
+        const CLOCAL;
+        /// Use RTS/CTS hardware flow control.
+        #[cfg(not(target_os = "redox"))]
+        const CRTSCTS;
+    }
+}
+
+impl ControlFlags {
+    /// The bits holding the character size.
+    pub const CSIZE: ControlFlags = ControlFlags(attr::CSIZE);
+    /// Five bits per character.
+    pub const CS5: ControlFlags = ControlFlags(attr::CS5);
+    /// Six bits per character.
+    pub const CS6: ControlFlags = ControlFlags(attr::CS6);
+    /// Seven bits per character.
+    pub const CS7: ControlFlags = ControlFlags(attr::CS7);
+    /// Eight bits per character.
+    pub const CS8: ControlFlags = ControlFlags(attr::CS8);
+}
+
+flags! {
+    /// The local flags, controlling the line discipline.
+    pub struct LocalFlags {
+        /// Send signals for the INTR, QUIT and SUSP characters.
+        const ISIG;
+        /// Buffer and edit the input by lines.
+        const ICANON;
+        /// Write the input back out.
+        const ECHO;
+        /// Make the ERASE character erase the previous character, with `ICANON`.
+        const ECHOE;
+        /// Make the KILL character erase the line, with `ICANON`.
+        const ECHOK;
+        /// Write NL back out even without `ECHO`, with `ICANON`.
+        const ECHONL;
+        /// Do not flush the queues when sending signals.
+        const NOFLSH;
+        /// Send `SIGTTOU` to background processes writing to the terminal.
+        const TOSTOP;
+        /// Enable the implementation defined input processing.
+        const IEXTEN;
+    }
+}
+
+/// A control character, a special input byte or a read setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ControlChar {
+    /// End of file (`VEOF`), ctrl-d by default.
+    Eof,
+    /// Additional end of line (`VEOL`).
+    Eol,
+    /// Erase the previous character (`VERASE`).
+    Erase,
+    /// Send `SIGINT` (`VINTR`), ctrl-c by default.
+    Intr,
+    /// Erase the line (`VKILL`), ctrl-u by default.
+    Kill,
+    /// Send `SIGQUIT` (`VQUIT`), `ctrl-\` by default.
+    Quit,
+    /// Resume the output (`VSTART`), ctrl-q by default.
+    Start,
+    /// Pause the output (`VSTOP`), ctrl-s by default.
+    Stop,
+    /// Send `SIGTSTP` (`VSUSP`), ctrl-z by default.
+    Susp,
+    /// The minimum number of bytes for a read to return, without `ICANON` (`VMIN`).
+    Min,
+    /// The read timeout in tenths of a second, without `ICANON` (`VTIME`).
+    Time,
+}
+
+impl ControlChar {
+    fn index(self) -> usize {
+        match self {
+            ControlChar::Eof => attr::VEOF,
+            ControlChar::Eol => attr::VEOL,
+            ControlChar::Erase => attr::VERASE,
+            ControlChar::Intr => attr::VINTR,
+            ControlChar::Kill => attr::VKILL,
+            ControlChar::Quit => attr::VQUIT,
+            ControlChar::Start => attr::VSTART,
+            ControlChar::Stop => attr::VSTOP,
+            ControlChar::Susp => attr::VSUSP,
+            ControlChar::Min => attr::VMIN,
+            ControlChar::Time => attr::VTIME,
+        }
+    }
+}
+
+macro_rules! speeds {
+    ($($(#[cfg($cfg:meta)])* $name:ident = $baud:expr,)*) => {
+        /// A line speed.
+        #[allow(missing_docs)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Speed {
+            $($(#[cfg($cfg)])* $name,)*
+        }
+
+        impl Speed {
+            /// Get the speed for the given baud rate, if it is supported.
+            pub fn from_baud(baud: u32) -> Option<Speed> {
+                match baud {
+                    $($(#[cfg($cfg)])* $baud => Some(Speed::$name),)*
+                    _ => None,
+                }
+            }
+
+            /// Get the baud rate of this speed.
+            ///
+            /// `B0` is not a rate, but means that the modem line is hung up.
+            pub fn baud(self) -> u32 {
+                match self {
+                    $($(#[cfg($cfg)])* Speed::$name => $baud,)*
+                }
+            }
+
+            fn from_sys(speed: attr::Speed) -> Option<Speed> {
+                $($(#[cfg($cfg)])* {
+                    if speed == attr::$name {
+                        return Some(Speed::$name);
+                    }
+                })*
+                None
+            }
+
+            fn to_sys(self) -> attr::Speed {
+                match self {
+                    $($(#[cfg($cfg)])* Speed::$name => attr::$name,)*
+                }
+            }
+        }
+    };
+}
+
+speeds! {
+    B0 = 0,
+    B50 = 50,
+    B75 = 75,
+    B110 = 110,
+    B134 = 134,
+    B150 = 150,
+    B200 = 200,
+    B300 = 300,
+    B600 = 600,
+    B1200 = 1200,
+    B1800 = 1800,
+    B2400 = 2400,
+    B4800 = 4800,
+    B9600 = 9600,
+    B19200 = 19200,
+    B38400 = 38400,
+    B57600 = 57600,
+    B115200 = 115200,
+    B230400 = 230400,
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    B460800 = 460800,
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    B500000 = 500000,
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    B576000 = 576000,
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    B921600 = 921600,
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    B1000000 = 1000000,
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    B1152000 = 1152000,
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    B1500000 = 1500000,
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    B2000000 = 2000000,
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    B2500000 = 2500000,
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    B3000000 = 3000000,
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    B3500000 = 3500000,
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    B4000000 = 4000000,
+}
+
+/// The attributes of a terminal.
+///
+/// Two attributes are equal when they have the same flags, control characters and speeds.
+#[derive(Clone, Copy)]
+pub struct Termios {
+    ios: sys::Termios,
+}
+
+impl PartialEq for Termios {
+    fn eq(&self, other: &Termios) -> bool {
+        self.ios.c_iflag == other.ios.c_iflag && self.ios.c_oflag == other.ios.c_oflag &&
+        self.ios.c_cflag == other.ios.c_cflag && self.ios.c_lflag == other.ios.c_lflag &&
+        self.ios.c_cc == other.ios.c_cc &&
+        attr::input_speed(&self.ios) == attr::input_speed(&other.ios) &&
+        attr::output_speed(&self.ios) == attr::output_speed(&other.ios)
+    }
+}
+
+impl Eq for Termios {}
+
+impl Termios {
+    /// Get the attributes of the terminal behind `fd`.
+    pub fn get(fd: RawFd) -> io::Result<Termios> {
+        attr::get_terminal_attr(fd).map(|ios| Termios { ios })
+    }
+
+    /// Set these attributes on the terminal behind `fd`, right away.
+    pub fn set(&self, fd: RawFd) -> io::Result<()> {
+        attr::set_terminal_attr(fd, &self.ios)
+    }
+
+    /// Change the attributes to raw mode, as `cfmakeraw(3)` does.
+    ///
+    /// See the `raw` module for what raw mode means.
+    pub fn make_raw(&mut self) {
+        attr::raw_terminal_attr(&mut self.ios);
+    }
+
+    /// Get the input flags.
+    pub fn input_flags(&self) -> InputFlags {
+        InputFlags(self.ios.c_iflag)
+    }
+
+    /// Set the input flags.
+    pub fn set_input_flags(&mut self, flags: InputFlags) {
+        self.ios.c_iflag = flags.0;
+    }
+
+    /// Get the output flags.
+    pub fn output_flags(&self) -> OutputFlags {
+        OutputFlags(self.ios.c_oflag)
+    }
+
+    /// Set the output flags.
+    pub fn set_output_flags(&mut self, flags: OutputFlags) {
+        self.ios.c_oflag = flags.0;
+    }
+
+    /// Get the control flags.
+    pub fn control_flags(&self) -> ControlFlags {
+        ControlFlags(self.ios.c_cflag)
+    }
+
+    /// Set the control flags.
+    ///
+    /// On some platforms, the speeds are held in the control flags too, so they should be changed
+    /// from the value returned by `control_flags`, rather than be built from scratch.
+    pub fn set_control_flags(&mut self, flags: ControlFlags) {
+        self.ios.c_cflag = flags.0;
+    }
+
+    /// Get the local flags.
+    pub fn local_flags(&self) -> LocalFlags {
+        LocalFlags(self.ios.c_lflag)
+    }
+
+    /// Set the local flags.
+    pub fn set_local_flags(&mut self, flags: LocalFlags) {
+        self.ios.c_lflag = flags.0;
+    }
+
+    /// Get a control character.
+    pub fn control_char(&self, cc: ControlChar) -> u8 {
+        self.ios.c_cc[cc.index()]
+    }
+
+    /// Set a control character.
+    pub fn set_control_char(&mut self, cc: ControlChar, value: u8) {
+        self.ios.c_cc[cc.index()] = value;
+    }
+
+    /// Get the input speed, or `None` if it is not one of the known speeds.
+    pub fn input_speed(&self) -> Option<Speed> {
+        Speed::from_sys(attr::input_speed(&self.ios))
+    }
+
+    /// Get the output speed, or `None` if it is not one of the known speeds.
+    pub fn output_speed(&self) -> Option<Speed> {
+        Speed::from_sys(attr::output_speed(&self.ios))
+    }
+
+    /// Set the input speed.
+    pub fn set_input_speed(&mut self, speed: Speed) -> io::Result<()> {
+        attr::set_input_speed(&mut self.ios, speed.to_sys())
+    }
+
+    /// Set the output speed.
+    pub fn set_output_speed(&mut self, speed: Speed) -> io::Result<()> {
+        attr::set_output_speed(&mut self.ios, speed.to_sys())
+    }
+
+    /// Set both the input and the output speed.
+    pub fn set_speed(&mut self, speed: Speed) -> io::Result<()> {
+        self.set_input_speed(speed)?;
+        self.set_output_speed(speed)
+    }
+}
+
+impl fmt::Debug for Termios {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Termios")
+            .field("input_flags", &self.input_flags())
+            .field("output_flags", &self.output_flags())
+            .field("control_flags", &self.control_flags())
+            .field("local_flags", &self.local_flags())
+            .field("input_speed", &self.input_speed())
+            .field("output_speed", &self.output_speed())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::os::unix::io::AsRawFd;
+    use sys::tty::get_tty;
+
+    #[test]
+    fn test_flags() {
+        let mut flags = LocalFlags::ICANON | LocalFlags::ECHO;
+        assert!(flags.contains(LocalFlags::ECHO));
+        assert!(!flags.contains(LocalFlags::ECHO | LocalFlags::ISIG));
+        assert!(flags.intersects(LocalFlags::ECHO | LocalFlags::ISIG));
+
+        flags.set(LocalFlags::ECHO, false);
+        flags.insert(LocalFlags::ISIG);
+        assert_eq!(flags, LocalFlags::ICANON | LocalFlags::ISIG);
+        assert_eq!(flags - LocalFlags::ICANON - LocalFlags::ISIG, LocalFlags::empty());
+        assert_eq!(format!("{:?}", LocalFlags::ECHO), "LocalFlags(ECHO)");
+
+        // A flag without a name, such as `IUTF8` on Linux.
+        let flags = InputFlags::from_bits_retain(InputFlags::ICRNL.bits() | 0o40000);
+        assert!(flags.contains(InputFlags::ICRNL));
+        assert_eq!((flags - InputFlags::ICRNL).bits(), 0o40000);
+    }
+
+    #[test]
+    fn test_speed() {
+        assert_eq!(Speed::from_baud(9600), Some(Speed::B9600));
+        assert_eq!(Speed::from_baud(9601), None);
+        assert_eq!(Speed::B115200.baud(), 115200);
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        {
+            assert_eq!(Speed::from_baud(921600), Some(Speed::B921600));
+            assert_eq!(Speed::from_sys(Speed::B4000000.to_sys()), Some(Speed::B4000000));
+        }
+    }
+
+    #[test]
+    fn test_termios() {
+        let tty = get_tty().unwrap();
+        let mut ios = Termios::get(tty.as_raw_fd()).unwrap();
+        assert_eq!(ios, Termios::get(tty.as_raw_fd()).unwrap());
+
+        ios.set_control_char(ControlChar::Min, 3);
+        assert_eq!(ios.control_char(ControlChar::Min), 3);
+
+        ios.set_speed(Speed::B19200).unwrap();
+        assert_eq!(ios.input_speed(), Some(Speed::B19200));
+        assert_eq!(ios.output_speed(), Some(Speed::B19200));
+
+        let mut raw = ios;
+        raw.make_raw();
+        assert!(!raw.local_flags().intersects(LocalFlags::ICANON | LocalFlags::ECHO));
+        assert!(!raw.output_flags().contains(OutputFlags::OPOST));
+        assert_eq!(raw.control_flags() & ControlFlags::CSIZE, ControlFlags::CS8);
+        assert!(raw != ios);
+    }
+}